@@ -1,14 +1,23 @@
-use std::str::FromStr;
+use std::{fs, path::PathBuf, str::FromStr};
 
-use clap::{Arg, ArgAction, ArgMatches};
+use clap::{value_parser, Arg, ArgAction, ArgMatches};
 use odra::{
-    casper_types::{CLType, CLValue, RuntimeArgs},
+    casper_types::{
+        bytesrepr::{OPTION_NONE_TAG, OPTION_SOME_TAG, RESULT_ERR_TAG, RESULT_OK_TAG},
+        CLType, CLValue, RuntimeArgs,
+    },
     schema::casper_contract_schema::{Argument, CustomType, Entrypoint, NamedCLType, Type},
 };
 use serde_json::Value;
 use thiserror::Error;
 
-use crate::{types, CustomTypeSet};
+use crate::{
+    backend::Backend,
+    config::{ENV_GAS, ENV_PAYMENT},
+    livenet,
+    output::OutputFormat,
+    types, CustomTypeSet,
+};
 
 #[derive(Debug, Error)]
 pub enum ArgsError {
@@ -20,6 +29,8 @@ pub enum ArgsError {
     ArgNotFound(String),
     #[error("Arg type not found: {0}")]
     ArgTypeNotFound(String),
+    #[error("Invalid JSON value: {0}")]
+    JsonError(String),
 }
 
 /// A typed command argument.
@@ -52,9 +63,13 @@ impl CommandArg {
 
 impl From<CommandArg> for Arg {
     fn from(arg: CommandArg) -> Self {
+        let value_name = match &arg.ty {
+            NamedCLType::Custom(name) => name.clone(),
+            ty => format!("{:?}", ty),
+        };
         let result = Arg::new(&arg.name)
             .long(arg.name)
-            .value_name(format!("{:?}", arg.ty))
+            .value_name(value_name)
             .required(arg.required)
             .help(arg.description);
 
@@ -69,12 +84,175 @@ pub fn entry_point_args(entry_point: &Entrypoint, types: &CustomTypeSet) -> Vec<
     entry_point
         .arguments
         .iter()
-        .flat_map(|arg| flat_arg(arg, types, false))
-        .flatten()
-        .map(Into::into)
+        .flat_map(|arg| {
+            let parts = flat_arg(arg, types, false).unwrap_or_default();
+            match contains_custom_type(&arg.ty.0) {
+                true => with_json_arg(arg, parts),
+                false => parts.into_iter().map(Into::into).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Adds the `--<arg>-json` alternative to the flattened args of a custom-typed argument.
+///
+/// If the JSON value is passed, the flattened args are not required.
+fn with_json_arg(arg: &Argument, parts: Vec<CommandArg>) -> Vec<Arg> {
+    let json_arg_name = json_arg_name(&arg.name);
+    let json_arg = Arg::new(&json_arg_name)
+        .long(&json_arg_name)
+        .value_name("JSON")
+        .help(format!(
+            "The `{}` arg as JSON, or @<file> to read it from a file",
+            arg.name
+        ))
+        .required(false)
+        .conflicts_with_all(parts.iter().map(|part| part.name.clone()))
+        .action(ArgAction::Set);
+
+    parts
+        .into_iter()
+        .map(|part| {
+            let required = part.required;
+            let part = Arg::from(part).required(false);
+            match required {
+                true => part.required_unless_present(&json_arg_name),
+                false => part,
+            }
+        })
+        .chain(std::iter::once(json_arg))
         .collect()
 }
 
+/// Returns the typed args of the entry point, the parts of the args passed as JSON are skipped.
+pub(crate) fn entry_point_command_args(
+    entry_point: &Entrypoint,
+    args: &ArgMatches,
+    types: &CustomTypeSet,
+) -> Vec<CommandArg> {
+    entry_point
+        .arguments
+        .iter()
+        .filter(|arg| !is_present(args, &json_arg_name(&arg.name)))
+        .flat_map(|arg| flat_arg(arg, types, false).unwrap_or_default())
+        .collect()
+}
+
+/// Returns the required args which are not present in the parsed input.
+///
+/// The payload args of an enum variant are required only if the variant is selected.
+/// The payload args of the variant selected in the input are returned as required, the payload
+/// args of all the variants of a missing enum arg are returned as optional, so they can be
+/// prompted for once the variant is known.
+pub(crate) fn missing(command_args: Vec<CommandArg>, args: &ArgMatches) -> Vec<CommandArg> {
+    let selectors = command_args
+        .iter()
+        .filter(|arg| is_variant_selector(arg))
+        .map(|arg| arg.name.clone())
+        .collect::<Vec<_>>();
+    let selectors = selectors.iter().map(String::as_str).collect::<Vec<_>>();
+    command_args
+        .into_iter()
+        .filter(|arg| !is_present(args, &arg.name))
+        .filter_map(|mut arg| match payload_variant(&selectors, &arg.name) {
+            Some((selector, variant)) => match args.try_get_one::<String>(selector) {
+                Ok(Some(selected)) if selected.to_lowercase() == variant => {
+                    arg.required = true;
+                    Some(arg)
+                }
+                Ok(Some(_)) => None,
+                _ => Some(arg),
+            },
+            None => arg.required.then_some(arg),
+        })
+        .collect()
+}
+
+/// Checks if the arg selects the variant of an enum, the structs are flattened into their fields.
+pub(crate) fn is_variant_selector(arg: &CommandArg) -> bool {
+    matches!(arg.ty, NamedCLType::Custom(_)) && !arg.is_list_element
+}
+
+/// Finds the enum arg and the variant the given arg is a payload of.
+///
+/// The variant is returned lowercased, as in the names of the payload args.
+pub(crate) fn payload_variant<'a, 'b>(
+    selectors: &[&'a str],
+    name: &'b str,
+) -> Option<(&'a str, &'b str)> {
+    selectors
+        .iter()
+        .filter_map(|selector| {
+            let path = name.strip_prefix(selector)?.strip_prefix('.')?;
+            path.split('.').next().map(|variant| (*selector, variant))
+        })
+        // a payload can hold an enum as well, the closest one is selected
+        .max_by_key(|(selector, _)| selector.len())
+}
+
+fn is_present(args: &ArgMatches, name: &str) -> bool {
+    args.try_contains_id(name).unwrap_or_default()
+}
+
+/// Checks if the value can be passed as the arg.
+///
+/// An enum is selected by the name of its variant, any other value must be serializable
+/// into the type of the arg.
+pub(crate) fn validate(
+    arg: &CommandArg,
+    value: &str,
+    types: &CustomTypeSet,
+) -> Result<(), ArgsError> {
+    match &arg.ty {
+        NamedCLType::Custom(name) => match find_custom_type(name, types)? {
+            CustomType::Enum { variants, .. } => variants
+                .iter()
+                .any(|variant| variant.name.eq_ignore_ascii_case(value))
+                .then_some(())
+                .ok_or(ArgsError::DecodingError(format!(
+                    "Unknown variant `{}` of {}",
+                    value, name
+                ))),
+            CustomType::Struct { .. } => Err(ArgsError::ArgTypeNotFound(name.clone())),
+        },
+        ty => types::into_bytes(ty, value).map(|_| ()).map_err(Into::into),
+    }
+}
+
+fn variant_arg_name(name: &str, variant: &str) -> String {
+    format!("{}.{}", name, variant.to_lowercase())
+}
+
+fn json_arg_name(name: &str) -> String {
+    format!("{}-json", name)
+}
+
+pub(crate) fn contains_custom_type(ty: &NamedCLType) -> bool {
+    match ty {
+        NamedCLType::Custom(_) => true,
+        NamedCLType::Option(ty) | NamedCLType::List(ty) => contains_custom_type(ty),
+        NamedCLType::Result { ok, err } => contains_custom_type(ok) || contains_custom_type(err),
+        NamedCLType::Map { key, value } => contains_custom_type(key) || contains_custom_type(value),
+        NamedCLType::Tuple1(ty) => ty.iter().any(|ty| contains_custom_type(ty)),
+        NamedCLType::Tuple2(ty) => ty.iter().any(|ty| contains_custom_type(ty)),
+        NamedCLType::Tuple3(ty) => ty.iter().any(|ty| contains_custom_type(ty)),
+        _ => false,
+    }
+}
+
+fn find_custom_type<'a>(name: &str, types: &'a CustomTypeSet) -> Result<&'a CustomType, ArgsError> {
+    types
+        .iter()
+        .find(|ty| {
+            let type_name = match ty {
+                CustomType::Struct { name, .. } => &name.0,
+                CustomType::Enum { name, .. } => &name.0,
+            };
+            name == type_name
+        })
+        .ok_or(ArgsError::ArgTypeNotFound(name.to_string()))
+}
+
 fn flat_arg(
     arg: &Argument,
     types: &CustomTypeSet,
@@ -82,16 +260,7 @@ fn flat_arg(
 ) -> Result<Vec<CommandArg>, ArgsError> {
     match &arg.ty.0 {
         NamedCLType::Custom(name) => {
-            let matching_type = types
-                .iter()
-                .find(|ty| {
-                    let type_name = match ty {
-                        CustomType::Struct { name, .. } => &name.0,
-                        CustomType::Enum { name, .. } => &name.0,
-                    };
-                    name == type_name
-                })
-                .ok_or(ArgsError::ArgTypeNotFound(name.clone()))?;
+            let matching_type = find_custom_type(name, types)?;
 
             match matching_type {
                 CustomType::Struct { members, .. } => {
@@ -110,19 +279,42 @@ fn flat_arg(
                     Ok(commands.into_iter().flatten().collect())
                 }
                 CustomType::Enum { variants, .. } => {
-                    let commands = variants
+                    // the variant is selected by name, the payload args are required
+                    // only if the selected variant carries data
+                    let variant_names = variants
                         .iter()
+                        .map(|variant| variant.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let description = match &arg.description {
+                        Some(description) if !description.is_empty() => {
+                            format!("{} [variants: {}]", description, variant_names)
+                        }
+                        _ => format!("[variants: {}]", variant_names),
+                    };
+                    let selector = CommandArg::new(
+                        &arg.name,
+                        &description,
+                        NamedCLType::Custom(name.clone()),
+                        !arg.optional,
+                        is_list_element,
+                    );
+                    let payloads = variants
+                        .iter()
+                        .filter(|variant| variant.ty.0 != NamedCLType::Unit)
                         .map(|variant| {
                             let variant_arg = Argument {
-                                name: format!("{}.{}", arg.name, variant.name.to_lowercase()),
+                                name: variant_arg_name(&arg.name, &variant.name),
                                 ty: variant.ty.clone(),
-                                optional: arg.optional,
+                                optional: true,
                                 description: variant.description.clone(),
                             };
                             flat_arg(&variant_arg, types, is_list_element)
                         })
                         .collect::<Result<Vec<_>, _>>()?;
-                    Ok(commands.into_iter().flatten().collect())
+                    Ok(std::iter::once(selector)
+                        .chain(payloads.into_iter().flatten())
+                        .collect())
                 }
             }
         }
@@ -151,9 +343,22 @@ pub fn compose(
     let mut runtime_args = RuntimeArgs::new();
 
     for arg in entry_point.arguments.iter() {
+        if let Some(input) = args
+            .try_get_one::<String>(&json_arg_name(&arg.name))
+            .ok()
+            .flatten()
+        {
+            let value = read_json(input)?;
+            let bytes = json_into_bytes(&arg.ty.0, &value, types)?;
+            let cl_type = types::named_cl_type_to_cl_type(&arg.ty.0);
+            runtime_args
+                .insert_cl_value(arg.name.clone(), CLValue::from_components(cl_type, bytes));
+            continue;
+        }
+
         let parts: Vec<CommandArg> = flat_arg(arg, types, false)?;
 
-        let cl_value = if parts.len() == 1 {
+        let cl_value = if !contains_custom_type(&arg.ty.0) {
             let input = args
                 .get_many::<String>(&arg.name)
                 .unwrap_or_default()
@@ -167,8 +372,7 @@ pub fn compose(
                 NamedCLType::List(inner) => {
                     let input = input
                         .iter()
-                        .map(|v| v.split(',').collect::<Vec<_>>())
-                        .flatten()
+                        .flat_map(|v| v.split(',').collect::<Vec<_>>())
                         .collect();
                     let bytes = types::vec_into_bytes(inner, input)?;
                     let cl_type = CLType::List(Box::new(types::named_cl_type_to_cl_type(inner)));
@@ -181,7 +385,7 @@ pub fn compose(
                 }
             }
         } else {
-            build_complex_arg(parts, args)?
+            build_complex_arg(parts, args, types)?
         };
         runtime_args.insert_cl_value(arg.name.clone(), cl_value);
     }
@@ -189,6 +393,144 @@ pub fn compose(
     Ok(runtime_args)
 }
 
+/// Parses the JSON value, or the content of the file if the input starts with `@`.
+fn read_json(input: &str) -> Result<Value, ArgsError> {
+    let json = match input.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| ArgsError::JsonError(format!("Couldn't read {}: {}", path, e)))?,
+        None => input.to_string(),
+    };
+    Value::from_str(&json).map_err(|e| ArgsError::JsonError(e.to_string()))
+}
+
+/// Serializes the JSON value into the bytes of the given type.
+///
+/// Custom types are validated against their schema - all the struct fields must be present,
+/// unknown fields and variants are rejected.
+fn json_into_bytes(
+    ty: &NamedCLType,
+    value: &Value,
+    types: &CustomTypeSet,
+) -> Result<Vec<u8>, ArgsError> {
+    let unexpected = || ArgsError::JsonError(format!("Unexpected value {} for {:?}", value, ty));
+    match (ty, value) {
+        (NamedCLType::Custom(name), _) => match find_custom_type(name, types)? {
+            CustomType::Struct { members, .. } => {
+                let fields = value.as_object().ok_or_else(unexpected)?;
+                if let Some(field) = fields
+                    .keys()
+                    .find(|field| members.iter().all(|member| &member.name != *field))
+                {
+                    return Err(ArgsError::JsonError(format!(
+                        "Unknown field `{}` of {}",
+                        field, name
+                    )));
+                }
+                let mut result = vec![];
+                for member in members {
+                    let field = fields.get(&member.name).ok_or_else(|| {
+                        ArgsError::JsonError(format!("Missing field `{}` of {}", member.name, name))
+                    })?;
+                    result.extend(json_into_bytes(&member.ty.0, field, types)?);
+                }
+                Ok(result)
+            }
+            CustomType::Enum { variants, .. } => {
+                // a unit variant is passed as a string, a variant carrying data
+                // as an object with a single key: `{ "Variant": payload }`
+                let (variant_name, payload) = match value {
+                    Value::String(variant_name) => (variant_name, &Value::Null),
+                    Value::Object(entries) if entries.len() == 1 => {
+                        entries.iter().next().ok_or_else(unexpected)?
+                    }
+                    _ => return Err(unexpected()),
+                };
+                let variant = variants
+                    .iter()
+                    .find(|variant| &variant.name == variant_name)
+                    .ok_or_else(|| {
+                        ArgsError::JsonError(format!(
+                            "Unknown variant `{}` of {}",
+                            variant_name, name
+                        ))
+                    })?;
+                let mut result = types::_to_bytes(variant.discriminant as u8)?;
+                result.extend(json_into_bytes(&variant.ty.0, payload, types)?);
+                Ok(result)
+            }
+        },
+        (NamedCLType::Option(_), Value::Null) => Ok(vec![OPTION_NONE_TAG]),
+        (NamedCLType::Option(inner), value) => {
+            let mut result = vec![OPTION_SOME_TAG];
+            result.extend(json_into_bytes(inner, value, types)?);
+            Ok(result)
+        }
+        (NamedCLType::List(inner), Value::Array(items)) => {
+            let mut result = types::_to_bytes(items.len() as u32)?;
+            for item in items {
+                result.extend(json_into_bytes(inner, item, types)?);
+            }
+            Ok(result)
+        }
+        (NamedCLType::Map { key, value: ty }, Value::Object(entries)) => {
+            let mut result = types::_to_bytes(entries.len() as u32)?;
+            for (k, v) in entries {
+                result.extend(json_into_bytes(key, &Value::String(k.clone()), types)?);
+                result.extend(json_into_bytes(ty, v, types)?);
+            }
+            Ok(result)
+        }
+        (NamedCLType::Result { ok, err }, Value::Object(entries)) if entries.len() == 1 => {
+            match entries.iter().next() {
+                Some((tag, value)) if tag == "ok" => {
+                    let mut result = vec![RESULT_OK_TAG];
+                    result.extend(json_into_bytes(ok, value, types)?);
+                    Ok(result)
+                }
+                Some((tag, value)) if tag == "err" => {
+                    let mut result = vec![RESULT_ERR_TAG];
+                    result.extend(json_into_bytes(err, value, types)?);
+                    Ok(result)
+                }
+                _ => Err(unexpected()),
+            }
+        }
+        (NamedCLType::Tuple1(tys), Value::Array(items)) if items.len() == 1 => {
+            json_tuple_into_bytes(tys, items, types)
+        }
+        (NamedCLType::Tuple2(tys), Value::Array(items)) if items.len() == 2 => {
+            json_tuple_into_bytes(tys, items, types)
+        }
+        (NamedCLType::Tuple3(tys), Value::Array(items)) if items.len() == 3 => {
+            json_tuple_into_bytes(tys, items, types)
+        }
+        (NamedCLType::ByteArray(_), Value::Array(items)) => {
+            let items = items.iter().map(Value::to_string).collect::<Vec<_>>();
+            Ok(types::into_bytes(ty, &items.join(","))?)
+        }
+        (NamedCLType::Unit, Value::Null) => Ok(vec![]),
+        (ty, Value::String(value)) if !contains_custom_type(ty) => {
+            Ok(types::into_bytes(ty, value)?)
+        }
+        (ty, Value::Number(_) | Value::Bool(_)) if !contains_custom_type(ty) => {
+            Ok(types::into_bytes(ty, &value.to_string())?)
+        }
+        _ => Err(unexpected()),
+    }
+}
+
+fn json_tuple_into_bytes(
+    tys: &[Box<NamedCLType>],
+    items: &[Value],
+    types: &CustomTypeSet,
+) -> Result<Vec<u8>, ArgsError> {
+    let mut result = vec![];
+    for (ty, item) in tys.iter().zip(items) {
+        result.extend(json_into_bytes(ty, item, types)?);
+    }
+    Ok(result)
+}
+
 #[derive(Debug, PartialEq)]
 struct ComposedArg<'a> {
     name: String,
@@ -241,10 +583,54 @@ impl<'a> ComposedArg<'a> {
     }
 }
 
-fn build_complex_arg(args: Vec<CommandArg>, matches: &ArgMatches) -> Result<CLValue, ArgsError> {
+fn build_complex_arg(
+    args: Vec<CommandArg>,
+    matches: &ArgMatches,
+    types: &CustomTypeSet,
+) -> Result<CLValue, ArgsError> {
     let mut current_group = ComposedArg::new("");
     let mut buffer: Vec<u8> = vec![];
+    // payload args of the enum variants that were not selected
+    let mut skipped_args: Vec<String> = vec![];
     for arg in args {
+        if skipped_args
+            .iter()
+            .any(|name| arg.name == *name || arg.name.starts_with(&format!("{}.", name)))
+        {
+            continue;
+        }
+        if let NamedCLType::Custom(name) = &arg.ty {
+            if arg.is_list_element {
+                return Err(ArgsError::DecodingError(format!(
+                    "Enum `{}` in a list must be passed as JSON",
+                    arg.name
+                )));
+            }
+            current_group.flush(&mut buffer)?;
+            let variants = match find_custom_type(name, types)? {
+                CustomType::Enum { variants, .. } => variants,
+                CustomType::Struct { .. } => return Err(ArgsError::ArgTypeNotFound(name.clone())),
+            };
+            let variant_name = matches
+                .get_one::<String>(&arg.name)
+                .ok_or(ArgsError::ArgNotFound(arg.name.clone()))?;
+            let variant = variants
+                .iter()
+                .find(|variant| variant.name.eq_ignore_ascii_case(variant_name))
+                .ok_or(ArgsError::DecodingError(format!(
+                    "Unknown variant `{}` of {}",
+                    variant_name, name
+                )))?;
+            buffer.extend(types::_to_bytes(variant.discriminant as u8)?);
+            skipped_args.extend(
+                variants
+                    .iter()
+                    .filter(|v| v.name != variant.name)
+                    .map(|v| variant_arg_name(&arg.name, &v.name)),
+            );
+            continue;
+        }
+
         let args = matches
             .get_many::<String>(&arg.name)
             .ok_or(ArgsError::ArgNotFound(arg.name.clone()))?
@@ -253,18 +639,13 @@ fn build_complex_arg(args: Vec<CommandArg>, matches: &ArgMatches) -> Result<CLVa
         let ty = arg.ty;
         let is_list_element = arg.is_list_element;
 
-        let parts = arg
-            .name
-            .split(".")
-            .map(|s| s.to_string())
-            .collect::<Vec<_>>();
-        let parent = parts[parts.len() - 2].clone();
+        let parent = arg.name.rsplit('.').nth(1).unwrap_or_default().to_string();
 
-        if &current_group.name != &parent && is_list_element {
+        if current_group.name != parent && is_list_element {
             current_group.flush(&mut buffer)?;
             current_group = ComposedArg::new(&parent);
             current_group.add((ty, args));
-        } else if &current_group.name == &parent && is_list_element {
+        } else if current_group.name == parent && is_list_element {
             current_group.add((ty, args));
         } else {
             current_group.flush(&mut buffer)?;
@@ -281,46 +662,17 @@ pub fn decode<'a>(
     ty: &Type,
     types: &'a CustomTypeSet,
 ) -> Result<(String, &'a [u8]), ArgsError> {
-    match &ty.0 {
-        NamedCLType::Custom(name) => {
-            let matching_type = types
-                .iter()
-                .find(|ty| {
-                    let type_name = match ty {
-                        CustomType::Struct { name, .. } => &name.0,
-                        CustomType::Enum { name, .. } => &name.0,
-                    };
-                    name == type_name
-                })
-                .ok_or(ArgsError::ArgTypeNotFound(name.clone()))?;
-            let mut bytes = bytes;
-
-            match matching_type {
-                CustomType::Struct { members, .. } => {
-                    let mut decoded = "{ ".to_string();
-                    for field in members {
-                        let (value, rem) = decode(bytes, &field.ty, types)?;
-                        decoded.push_str(format!(" \"{}\": \"{}\",", field.name, value).as_str());
-                        bytes = rem;
-                    }
-                    decoded.pop();
-                    decoded.push_str(" }");
-                    Ok((to_json(&decoded)?, bytes))
-                }
-                CustomType::Enum { variants, .. } => {
-                    let ty = Type(NamedCLType::U8);
-                    let (value, rem) = decode(bytes, &ty, types)?;
-                    let discriminant = types::parse_value::<u16>(&value)?;
+    if contains_custom_type(&ty.0) {
+        let (value, bytes) = decode_value(bytes, &ty.0, types)?;
+        let decoded = match value {
+            Value::String(value) => value,
+            value => serde_json::to_string_pretty(&value)
+                .map_err(|_| ArgsError::DecodingError("Invalid JSON".to_string()))?,
+        };
+        return Ok((decoded, bytes));
+    }
 
-                    let variant = variants
-                        .iter()
-                        .find(|v| v.discriminant == discriminant)
-                        .ok_or(ArgsError::DecodingError("Variant not found".to_string()))?;
-                    bytes = rem;
-                    Ok((variant.name.clone(), bytes))
-                }
-            }
-        }
+    match &ty.0 {
         NamedCLType::List(inner) => {
             let ty = Type(*inner.clone());
             let mut bytes = bytes;
@@ -333,12 +685,11 @@ pub fn decode<'a>(
                 bytes = rem;
                 decoded.push_str(format!("{},", value).as_str());
             }
-            decoded.pop();
-            decoded.push_str("]");
-            match &**inner {
-                NamedCLType::Custom(_) => Ok((to_json(&decoded)?, bytes)),
-                _ => Ok((decoded, bytes)),
+            if len > 0 {
+                decoded.pop();
             }
+            decoded.push(']');
+            Ok((decoded, bytes))
         }
         _ => {
             let result = types::from_bytes(&ty.0, bytes)?;
@@ -347,11 +698,206 @@ pub fn decode<'a>(
     }
 }
 
-fn to_json(str: &str) -> Result<String, ArgsError> {
-    let json =
-        Value::from_str(str).map_err(|_| ArgsError::DecodingError("Invalid JSON".to_string()))?;
-    serde_json::to_string_pretty(&json)
-        .map_err(|_| ArgsError::DecodingError("Invalid JSON".to_string()))
+/// Decodes a value containing custom types into JSON.
+///
+/// A struct is decoded into an object, a unit enum variant into its name and a variant
+/// carrying data into an object with a single key: `{ "Variant": payload }`.
+/// Other values are kept as strings.
+pub(crate) fn decode_value<'a>(
+    bytes: &'a [u8],
+    ty: &NamedCLType,
+    types: &CustomTypeSet,
+) -> Result<(Value, &'a [u8]), ArgsError> {
+    match ty {
+        NamedCLType::Custom(name) => match find_custom_type(name, types)? {
+            CustomType::Struct { members, .. } => {
+                let mut bytes = bytes;
+                let mut fields = serde_json::Map::new();
+                for member in members {
+                    let (value, rem) = decode_value(bytes, &member.ty.0, types)?;
+                    fields.insert(member.name.clone(), value);
+                    bytes = rem;
+                }
+                Ok((Value::Object(fields), bytes))
+            }
+            CustomType::Enum { variants, .. } => {
+                let (discriminant, bytes) = types::_from_bytes::<u8>(bytes)?;
+                let variant = variants
+                    .iter()
+                    .find(|v| v.discriminant == discriminant as u16)
+                    .ok_or(ArgsError::DecodingError("Variant not found".to_string()))?;
+                match &variant.ty.0 {
+                    NamedCLType::Unit => Ok((Value::String(variant.name.clone()), bytes)),
+                    ty => {
+                        let (payload, bytes) = decode_value(bytes, ty, types)?;
+                        let mut value = serde_json::Map::new();
+                        value.insert(variant.name.clone(), payload);
+                        Ok((Value::Object(value), bytes))
+                    }
+                }
+            }
+        },
+        NamedCLType::List(inner) => {
+            let (len, mut bytes) = types::_from_bytes::<u32>(bytes)?;
+            let mut items = vec![];
+            for _ in 0..len {
+                let (value, rem) = decode_value(bytes, inner, types)?;
+                items.push(value);
+                bytes = rem;
+            }
+            Ok((Value::Array(items), bytes))
+        }
+        NamedCLType::Option(inner) if contains_custom_type(inner) => {
+            match types::_from_bytes::<u8>(bytes)? {
+                (OPTION_NONE_TAG, bytes) => Ok((Value::Null, bytes)),
+                (_, bytes) => decode_value(bytes, inner, types),
+            }
+        }
+        ty if contains_custom_type(ty) => Err(ArgsError::DecodingError(format!(
+            "Decoding {:?} is not supported",
+            ty
+        ))),
+        ty => {
+            let (value, bytes) = types::from_bytes(ty, bytes)?;
+            Ok((Value::String(value), bytes))
+        }
+    }
+}
+
+pub const NETWORK_ARG: &str = "__network";
+
+pub fn network_arg() -> Arg {
+    Arg::new(NETWORK_ARG)
+        .help("The network profile the deployed contracts are resolved against")
+        .long("network")
+        .env("ODRA_CLI_NETWORK")
+        .required(false)
+        .global(true)
+        .value_name("NAME")
+        .action(ArgAction::Set)
+}
+
+pub fn network(args: &ArgMatches) -> Option<&str> {
+    args.try_get_one::<String>(NETWORK_ARG)
+        .ok()
+        .flatten()
+        .map(String::as_str)
+}
+
+pub const CONTRACTS_FILE_ARG: &str = "__contracts_file";
+
+pub fn contracts_file_arg() -> Arg {
+    Arg::new(CONTRACTS_FILE_ARG)
+        .help("The file the deployed contracts are stored in, a .json file is stored as JSON")
+        .long("contracts-file")
+        .env("ODRA_CLI_CONTRACTS_FILE")
+        .required(false)
+        .global(true)
+        .value_name("PATH")
+        .value_parser(value_parser!(PathBuf))
+        .action(ArgAction::Set)
+}
+
+pub fn contracts_file(args: &ArgMatches) -> Option<PathBuf> {
+    args.try_get_one::<PathBuf>(CONTRACTS_FILE_ARG)
+        .ok()
+        .flatten()
+        .cloned()
+}
+
+pub const NODE_ADDRESS_ARG: &str = "__node_address";
+
+pub fn node_address_arg() -> Arg {
+    Arg::new(NODE_ADDRESS_ARG)
+        .help("The address of the Casper node the livenet backend is connected to")
+        .long("node-address")
+        .env(livenet::ENV_NODE_ADDRESS)
+        .required(false)
+        .global(true)
+        .value_name("URL")
+        .action(ArgAction::Set)
+}
+
+pub const CHAIN_NAME_ARG: &str = "__chain_name";
+
+pub fn chain_name_arg() -> Arg {
+    Arg::new(CHAIN_NAME_ARG)
+        .help("The name of the chain the livenet backend is connected to")
+        .long("chain-name")
+        .env(livenet::ENV_CHAIN_NAME)
+        .required(false)
+        .global(true)
+        .value_name("NAME")
+        .action(ArgAction::Set)
+}
+
+pub const SECRET_KEY_PATH_ARG: &str = "__secret_key_path";
+
+pub fn secret_key_path_arg() -> Arg {
+    Arg::new(SECRET_KEY_PATH_ARG)
+        .help("The secret key the livenet backend signs the deploys with")
+        .long("secret-key-path")
+        .env(livenet::ENV_SECRET_KEY_PATH)
+        .required(false)
+        .global(true)
+        .value_name("PATH")
+        .action(ArgAction::Set)
+}
+
+pub const BACKEND_ARG: &str = "__backend";
+
+pub fn backend_arg() -> Arg {
+    Arg::new(BACKEND_ARG)
+        .help("The backend the commands are executed on")
+        .long("backend")
+        .env("ODRA_CLI_BACKEND")
+        .required(false)
+        .global(true)
+        .value_name("BACKEND")
+        .value_parser(value_parser!(Backend))
+        .default_value("livenet")
+        .action(ArgAction::Set)
+}
+
+/// Returns `true` if the backend is selected by the flag or the environment variable.
+#[cfg(feature = "casper-vm")]
+pub fn is_backend_selected(args: &ArgMatches) -> bool {
+    use clap::parser::ValueSource;
+    matches!(
+        args.value_source(BACKEND_ARG),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+pub fn backend(args: &ArgMatches) -> Backend {
+    args.try_get_one::<Backend>(BACKEND_ARG)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(Backend::Livenet)
+}
+
+pub const OUTPUT_ARG: &str = "__output";
+
+pub fn output_arg() -> Arg {
+    Arg::new(OUTPUT_ARG)
+        .help("The format the command results and errors are printed in")
+        .long("output")
+        .env("ODRA_CLI_OUTPUT")
+        .required(false)
+        .global(true)
+        .value_name("FORMAT")
+        .value_parser(value_parser!(OutputFormat))
+        .default_value("text")
+        .action(ArgAction::Set)
+}
+
+pub fn output(args: &ArgMatches) -> OutputFormat {
+    args.try_get_one::<OutputFormat>(OUTPUT_ARG)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(OutputFormat::Text)
 }
 
 pub fn attached_value_arg() -> Arg {
@@ -363,15 +909,191 @@ pub fn attached_value_arg() -> Arg {
         .action(ArgAction::Set)
 }
 
+pub const INTERACTIVE_ARG: &str = "__interactive";
+
+pub fn interactive_arg() -> Arg {
+    Arg::new(INTERACTIVE_ARG)
+        .help("Prompts for the missing required args instead of failing")
+        .long("interactive")
+        .required(false)
+        .global(true)
+        .action(ArgAction::SetTrue)
+}
+
+pub fn interactive(args: &ArgMatches) -> bool {
+    args.try_get_one::<bool>(INTERACTIVE_ARG)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Checks if the flag is set in the matches of the most nested subcommand.
+///
+/// The flags of an entry point or a deploy are not global, so they are looked up
+/// where the parser stored them.
+pub fn nested_flag(args: &ArgMatches, id: &str) -> bool {
+    match args.subcommand() {
+        Some((_, args)) => nested_flag(args, id),
+        None => args
+            .try_get_one::<bool>(id)
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or_default(),
+    }
+}
+
+pub const SIMULATE_ARG: &str = "__simulate";
+
+pub fn simulate_arg() -> Arg {
+    Arg::new(SIMULATE_ARG)
+        .help(
+            "Executes the entry point locally without sending a deploy, the changes are discarded",
+        )
+        .long("simulate")
+        .required(false)
+        .action(ArgAction::SetTrue)
+}
+
+pub const RECEIPT_ARG: &str = "__receipt";
+
+pub fn receipt_arg() -> Arg {
+    Arg::new(RECEIPT_ARG)
+        .help("Prints the receipt of the call, on a livenet the deploy is looked up in the latest blocks")
+        .long("receipt")
+        .required(false)
+        .conflicts_with(SIMULATE_ARG)
+        .action(ArgAction::SetTrue)
+}
+
+pub const RESUME_ARG: &str = "__resume";
+
+pub fn resume_arg() -> Arg {
+    Arg::new(RESUME_ARG)
+        .help("Continues the previous deployment, the contracts already stored are reused")
+        .long("resume")
+        .required(false)
+        .action(ArgAction::SetTrue)
+}
+
+pub const GAS_ARG: &str = "__gas";
+
+pub fn gas_arg() -> Arg {
+    Arg::new(GAS_ARG)
+        .help("The gas limit of the call in motes [default: 20 CSPR]")
+        .long("gas")
+        .env(ENV_GAS)
+        .required(false)
+        .value_name("MOTES")
+        .value_parser(value_parser!(u64))
+        .action(ArgAction::Set)
+}
+
+pub fn gas(args: &ArgMatches) -> Option<u64> {
+    args.try_get_one::<u64>(GAS_ARG).ok().flatten().copied()
+}
+
+pub const PAYMENT_ARG: &str = "__payment";
+
+pub fn payment_arg() -> Arg {
+    Arg::new(PAYMENT_ARG)
+        .help("The gas limit of the deploys in motes, unless set by the deploy script")
+        .long("payment")
+        .env(ENV_PAYMENT)
+        .required(false)
+        .value_name("MOTES")
+        .value_parser(value_parser!(u64))
+        .action(ArgAction::Set)
+}
+
+pub fn payment(args: &ArgMatches) -> Option<u64> {
+    args.try_get_one::<u64>(PAYMENT_ARG).ok().flatten().copied()
+}
+
+pub const ESTIMATE_GAS_ARG: &str = "__estimate_gas";
+
+#[cfg(feature = "casper-vm")]
+pub fn estimate_gas_arg() -> Arg {
+    Arg::new(ESTIMATE_GAS_ARG)
+        .help("Prints the gas consumed by the entry point on a local Casper VM; the contracts are deployed there by the deploy script, the state of the network is not used")
+        .long("estimate-gas")
+        .required(false)
+        .conflicts_with(SIMULATE_ARG)
+        .action(ArgAction::SetTrue)
+}
+
+pub const EVENTS_FROM_ARG: &str = "__from";
+
+pub fn events_from_arg() -> Arg {
+    Arg::new(EVENTS_FROM_ARG)
+        .help("The index of the first event")
+        .long("from")
+        .required(false)
+        .value_name("N")
+        .value_parser(value_parser!(u32))
+        .default_value("0")
+        .action(ArgAction::Set)
+}
+
+pub fn events_from(args: &ArgMatches) -> u32 {
+    args.try_get_one::<u32>(EVENTS_FROM_ARG)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or_default()
+}
+
+pub const EVENTS_LIMIT_ARG: &str = "__limit";
+
+pub fn events_limit_arg() -> Arg {
+    Arg::new(EVENTS_LIMIT_ARG)
+        .help("The maximum number of events to print")
+        .long("limit")
+        .required(false)
+        .value_name("N")
+        .value_parser(value_parser!(u32))
+        .conflicts_with(EVENTS_FOLLOW_ARG)
+        .action(ArgAction::Set)
+}
+
+pub fn events_limit(args: &ArgMatches) -> Option<u32> {
+    args.try_get_one::<u32>(EVENTS_LIMIT_ARG)
+        .ok()
+        .flatten()
+        .copied()
+}
+
+pub const EVENTS_FOLLOW_ARG: &str = "__follow";
+
+pub fn events_follow_arg() -> Arg {
+    Arg::new(EVENTS_FOLLOW_ARG)
+        .help("Keeps printing new events as they are emitted")
+        .long("follow")
+        .required(false)
+        .action(ArgAction::SetTrue)
+}
+
+pub fn events_follow(args: &ArgMatches) -> bool {
+    args.try_get_one::<bool>(EVENTS_FOLLOW_ARG)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod t {
     use clap::{Arg, Command};
     use odra::{
-        casper_types::{bytesrepr::Bytes, runtime_args, RuntimeArgs},
-        schema::casper_contract_schema::{NamedCLType, Type},
+        casper_types::{
+            bytesrepr::{Bytes, ToBytes},
+            runtime_args, RuntimeArgs, U512,
+        },
+        schema::casper_contract_schema::{Argument, NamedCLType, Type},
     };
 
-    use crate::test_utils::{self, NameMintInfo, PaymentInfo, PaymentVoucher};
+    use crate::test_utils::{self, NameMintInfo, PaymentInfo, PaymentMethod, PaymentVoucher};
 
     const NAMED_TOKEN_METADATA_BYTES: [u8; 50] = [
         4, 0, 0, 0, 107, 112, 111, 98, 0, 32, 74, 169, 209, 1, 0, 0, 1, 1, 226, 74, 54, 110, 186,
@@ -475,4 +1197,190 @@ mod t {
         };
         pretty_assertions::assert_eq!(args, expected);
     }
+
+    #[test]
+    fn test_compose_json() {
+        let entry_point = test_utils::mock_entry_point();
+        let types = test_utils::custom_types();
+
+        let cmd = Command::new("myprog").args(super::entry_point_args(&entry_point, &types));
+        let voucher = r#"{
+            "payment": {
+                "buyer": "hash-56fef1f62d86ab68655c2a5d1c8b9ed8e60d5f7e59736e9d4c215a40b10f4a22",
+                "payment_id": "id_001",
+                "amount": "666"
+            },
+            "names": [{
+                "label": "kpob",
+                "owner": "hash-f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a",
+                "token_expiration": 1000000
+            }],
+            "voucher_expiration": 2000000
+        }"#;
+        let args = cmd.clone().get_matches_from(vec![
+            "myprog",
+            "--voucher-json",
+            voucher,
+            "--signature",
+            "1,148",
+        ]);
+        let args = super::compose(&entry_point, &args, &types).unwrap();
+        let expected = runtime_args! {
+            "voucher" => PaymentVoucher::new(
+                PaymentInfo::new(
+                    "hash-56fef1f62d86ab68655c2a5d1c8b9ed8e60d5f7e59736e9d4c215a40b10f4a22",
+                    "id_001",
+                    "666"
+                ),
+                vec![
+                    NameMintInfo::new(
+                        "kpob",
+                        "hash-f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a",
+                        1000000
+                    )
+                ],
+                2000000
+            ),
+            "signature" => Bytes::from(vec![1u8, 148u8]),
+        };
+        pretty_assertions::assert_eq!(args, expected);
+
+        let invalid = voucher.replace("\"voucher_expiration\"", "\"expiration\"");
+        let args = cmd.get_matches_from(vec![
+            "myprog",
+            "--voucher-json",
+            &invalid,
+            "--signature",
+            "1,148",
+        ]);
+        assert!(super::compose(&entry_point, &args, &types).is_err());
+    }
+
+    #[test]
+    fn test_compose_enum() {
+        let types = test_utils::custom_types();
+        let mut entry_point = test_utils::mock_entry_point();
+        entry_point.arguments = vec![Argument::new(
+            "method",
+            "",
+            NamedCLType::Custom("PaymentMethod".to_string()),
+        )];
+        let cmd = Command::new("myprog").args(super::entry_point_args(&entry_point, &types));
+        let compose = |args: Vec<&str>| {
+            let matches = cmd.clone().try_get_matches_from(args).unwrap();
+            super::compose(&entry_point, &matches, &types)
+        };
+        let owner = "hash-f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a";
+
+        let args = compose(vec!["myprog", "--method", "Free"]).unwrap();
+        pretty_assertions::assert_eq!(args, runtime_args! { "method" => PaymentMethod::Free });
+
+        let args = compose(vec!["myprog", "--method", "Cspr", "--method.cspr", "100"]).unwrap();
+        let expected = runtime_args! { "method" => PaymentMethod::Cspr(U512::from(100)) };
+        pretty_assertions::assert_eq!(args, expected);
+
+        let args = compose(vec![
+            "myprog",
+            "--method",
+            "Token",
+            "--method.token.token",
+            owner,
+            "--method.token.amount",
+            "5",
+            "--method.cspr",
+            "100",
+        ])
+        .unwrap();
+        let expected = runtime_args! {
+            "method" => PaymentMethod::Token { token: owner.parse().unwrap(), amount: U512::from(5) }
+        };
+        pretty_assertions::assert_eq!(args, expected);
+
+        let json = format!(
+            r#"{{ "Token": {{ "token": "{}", "amount": "5" }} }}"#,
+            owner
+        );
+        let args = compose(vec!["myprog", "--method-json", &json]).unwrap();
+        pretty_assertions::assert_eq!(args, expected);
+
+        assert!(compose(vec!["myprog", "--method", "Cspr"]).is_err());
+        assert!(compose(vec!["myprog", "--method", "Card"]).is_err());
+    }
+
+    #[test]
+    fn test_missing_enum_payload() {
+        let types = test_utils::custom_types();
+        let mut entry_point = test_utils::mock_entry_point();
+        entry_point.arguments = vec![Argument::new(
+            "method",
+            "",
+            NamedCLType::Custom("PaymentMethod".to_string()),
+        )];
+        let cmd = Command::new("myprog")
+            .args(super::entry_point_args(&entry_point, &types))
+            .ignore_errors(true);
+        let missing = |args: Vec<&str>| {
+            let matches = cmd.clone().try_get_matches_from(args).unwrap();
+            super::missing(
+                super::entry_point_command_args(&entry_point, &matches, &types),
+                &matches,
+            )
+            .into_iter()
+            .map(|arg| (arg.name, arg.required))
+            .collect::<Vec<_>>()
+        };
+        let arg = |name: &str, required: bool| (name.to_string(), required);
+
+        pretty_assertions::assert_eq!(
+            missing(vec!["myprog"]),
+            vec![
+                arg("method", true),
+                arg("method.cspr", false),
+                arg("method.token.token", false),
+                arg("method.token.amount", false),
+            ]
+        );
+        pretty_assertions::assert_eq!(
+            missing(vec![
+                "myprog",
+                "--method",
+                "Token",
+                "--method.token.amount",
+                "5"
+            ]),
+            vec![arg("method.token.token", true)]
+        );
+        pretty_assertions::assert_eq!(missing(vec!["myprog", "--method", "Free"]), vec![]);
+    }
+
+    #[test]
+    fn test_decode_enum() {
+        let types = test_utils::custom_types();
+        let ty = Type(NamedCLType::List(Box::new(NamedCLType::Custom(
+            "PaymentMethod".to_string(),
+        ))));
+        let methods = vec![
+            PaymentMethod::Free,
+            PaymentMethod::Cspr(U512::from(100)),
+            PaymentMethod::Token {
+                token: "hash-f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a"
+                    .parse()
+                    .unwrap(),
+                amount: U512::from(5),
+            },
+        ];
+        let bytes = methods.to_bytes().unwrap();
+
+        let (result, rem) = super::decode(&bytes, &ty, &types).unwrap();
+        let expected = serde_json::json!([
+            "Free",
+            { "Cspr": "100" },
+            { "Token": {
+                "token": "Key::Hash(f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a)",
+                "amount": "5"
+            } }
+        ]);
+        pretty_assertions::assert_eq!(result, serde_json::to_string_pretty(&expected).unwrap());
+        assert!(rem.is_empty());
+    }
 }
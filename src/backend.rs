@@ -0,0 +1,136 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+};
+
+use clap::ValueEnum;
+use odra::host::HostEnv;
+#[cfg(feature = "casper-vm")]
+use odra_casper_test_vm::{CasperHost, CasperVm};
+use odra_vm::{OdraVm, OdraVmHost};
+
+use crate::{config, dry_run, Error};
+
+/// Parts of the panic messages of a contract execution failed on the host side.
+///
+/// The livenet backend executes the read-only calls locally, so a contract writing
+/// to the state there fails as well, like a dry run signing or transferring tokens.
+const REVERT_MESSAGES: [&str; 4] = [
+    "Revert: ",
+    "in LivenetEnv",
+    "Cannot cross call",
+    "in a dry run",
+];
+
+/// The panic messages of the livenet client failing to reach the node or to read its response.
+const NETWORK_MESSAGES: [&str; 3] = [
+    "Couldn't send request",
+    "Couldn't parse response",
+    "Couldn't get result",
+];
+
+/// The backend the [HostEnv] is built on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Backend {
+    /// A live Casper node configured by the livenet environment variables.
+    Livenet,
+    /// The in-memory Odra VM. The state is lost once the command completes.
+    OdraVm,
+    /// The in-memory Casper VM executing the contracts wasm, used to estimate gas.
+    #[cfg(feature = "casper-vm")]
+    #[value(skip)]
+    CasperVm,
+}
+
+impl Backend {
+    /// Builds a new host environment.
+    ///
+    /// The livenet backend is built only if its environment variables are set
+    /// and the secret keys can be loaded.
+    pub fn env(&self) -> Result<HostEnv, Error> {
+        match self {
+            Backend::Livenet => {
+                config::check_livenet()?;
+                config::check_secret_keys()?;
+                Ok(odra_casper_livenet_env::env())
+            }
+            Backend::OdraVm => Ok(HostEnv::new(OdraVmHost::new(OdraVm::new()))),
+            #[cfg(feature = "casper-vm")]
+            Backend::CasperVm => Ok(HostEnv::new(CasperHost::new(CasperVm::new()))),
+        }
+    }
+
+    /// Builds a host environment for a command which does not send a deploy.
+    ///
+    /// On the livenet backend, the calls are executed locally against the state of the node,
+    /// so no secret key is needed. The in-memory backends discard the state anyway.
+    pub fn dry_run_env(&self) -> Result<HostEnv, Error> {
+        match self {
+            Backend::Livenet => {
+                config::check_livenet()?;
+                Ok(dry_run::env())
+            }
+            _ => self.env(),
+        }
+    }
+
+    /// Returns `true` if the contracts deployed by the backend outlive a single command.
+    pub fn is_persistent(&self) -> bool {
+        matches!(self, Backend::Livenet)
+    }
+
+    /// Runs the given function and turns a panic of the backend into an error.
+    ///
+    /// The backends report a revert of a contract by panicking, the livenet client panics also
+    /// if the node can not be reached or its response is invalid. A revert is reported as
+    /// an execution failure, a failed request as a network failure and any other panic
+    /// as an unexpected error. The default panic hook is replaced while the function runs,
+    /// so the panic is not printed.
+    pub fn guard<T>(&self, f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        panic::set_hook(hook);
+        result.unwrap_or_else(|payload| Err(panic_error(panic_message(payload))))
+    }
+}
+
+/// Classifies the message of a panic raised by a backend.
+fn panic_error(message: String) -> Error {
+    let matches = |patterns: &[&str]| patterns.iter().any(|pattern| message.contains(pattern));
+    if matches(&REVERT_MESSAGES) {
+        Error::Reverted(message)
+    } else if matches(&NETWORK_MESSAGES) {
+        Error::Network(message)
+    } else {
+        Error::Unexpected(message)
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .unwrap_or_else(|| "Unknown error".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use crate::Error;
+
+    #[test]
+    fn test_guard() {
+        let backend = super::Backend::OdraVm;
+        let revert = backend.guard::<()>(|| panic!("Revert: ExecutionError(User(42)) - fail"));
+        assert!(matches!(revert, Err(Error::Reverted(_))));
+        let network = backend.guard::<()>(|| panic!("Couldn't send request"));
+        assert!(matches!(network, Err(Error::Network(_))));
+        let unexpected =
+            backend.guard::<()>(|| panic!("Key for account with index 1 is not loaded"));
+        assert!(matches!(unexpected, Err(Error::Unexpected(_))));
+        assert!(matches!(backend.guard(|| Ok(1)), Ok(1)));
+    }
+}
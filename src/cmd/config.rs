@@ -0,0 +1,88 @@
+use clap::{parser::ValueSource, ArgMatches, Command};
+use serde_derive::Serialize;
+
+use crate::{
+    config::{self, Config},
+    container::ContainerLocation,
+    output::CommandOutput,
+    Error,
+};
+
+pub(crate) const CONFIG_SUBCOMMAND: &str = "config";
+const SHOW_SUBCOMMAND: &str = "show";
+
+/// A value the CLI is configured with.
+#[derive(Debug, Serialize)]
+pub(crate) struct ConfigEntry {
+    pub key: String,
+    pub env: String,
+    pub value: Option<String>,
+    pub source: ConfigSource,
+}
+
+/// Where the value of a [ConfigEntry] comes from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ConfigSource {
+    Flag,
+    Env,
+    ConfigFile,
+    Unset,
+}
+
+/// Returns the command printing the configuration of the CLI.
+pub(crate) fn command() -> Command {
+    Command::new(CONFIG_SUBCOMMAND)
+        .about("Commands for inspecting the configuration of the CLI")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new(SHOW_SUBCOMMAND)
+                .about("Prints the configured values and where they come from"),
+        )
+}
+
+/// Runs a subcommand inspecting the configuration.
+///
+/// The config file has been applied to the environment variables and the flags
+/// have been exported, so the effective values are read from the environment.
+/// `applied` lists the variables set by the config file.
+pub(crate) fn run(
+    args: &ArgMatches,
+    location: &ContainerLocation,
+    applied: &[&str],
+) -> Result<CommandOutput, Error> {
+    match args.subcommand() {
+        Some((SHOW_SUBCOMMAND, args)) => {
+            let entries = Config::default()
+                .values()
+                .into_iter()
+                .map(|config_value| {
+                    let value = std::env::var(config_value.env).ok();
+                    let is_flag = config_value
+                        .flag
+                        .and_then(|flag| args.value_source(flag))
+                        .is_some_and(|source| source == ValueSource::CommandLine);
+                    let source = match &value {
+                        _ if is_flag => ConfigSource::Flag,
+                        None => ConfigSource::Unset,
+                        _ if applied.contains(&config_value.env) => ConfigSource::ConfigFile,
+                        _ => ConfigSource::Env,
+                    };
+                    ConfigEntry {
+                        key: config_value.key.to_string(),
+                        env: config_value.env.to_string(),
+                        value,
+                        source,
+                    }
+                })
+                .collect();
+            let file = config::path(location).filter(|path| path.exists());
+            Ok(CommandOutput::Config {
+                file: file.map(|path| path.to_string_lossy().to_string()),
+                entries,
+            })
+        }
+        _ => Err(Error::InvalidArgs("Subcommand not found".to_string())),
+    }
+}
@@ -1,21 +1,35 @@
-use anyhow::Result;
 use clap::ArgMatches;
-use odra::{host::HostEnv, schema::casper_contract_schema::Entrypoint};
+use odra::{
+    contract_def::HasIdent,
+    entry_point_callback::EntryPointsCaller,
+    host::{EntryPointsCallerProvider, HostEnv},
+    schema::{casper_contract_schema::Entrypoint, SchemaEntrypoints},
+    OdraContract,
+};
 
-use crate::{entry_point, CustomTypeSet};
+use crate::{
+    args::{self, CommandArg},
+    container::{self, ContractError},
+    entry_point,
+    output::{self, CommandOutput},
+    CustomTypeSet, DeployedContractsContainer, Error,
+};
 
-use super::OdraCommand;
+use super::{events::EventsCmd, OdraCommand};
 
 /// ContractCmd is a struct that represents a contract command in the Odra CLI.
 ///
 /// The contract command runs a contract with a given entry point.
 pub(crate) struct ContractCmd {
     name: String,
+    ident: String,
+    entry_points: Vec<Entrypoint>,
     commands: Vec<Box<dyn OdraCommand>>,
+    caller: fn(&HostEnv) -> EntryPointsCaller,
 }
 
 impl ContractCmd {
-    pub fn new<T: odra::schema::SchemaEntrypoints>(contract_name: String) -> Self {
+    pub fn new<T: SchemaEntrypoints + OdraContract>(contract_name: String) -> Self {
         let commands = T::schema_entrypoints()
             .into_iter()
             .map(|entry_point| {
@@ -24,10 +38,75 @@ impl ContractCmd {
                     entry_point,
                 }) as Box<dyn OdraCommand>
             })
+            .chain(std::iter::once(
+                Box::new(EventsCmd::new(contract_name.clone())) as Box<dyn OdraCommand>,
+            ))
             .collect::<Vec<_>>();
+        let entry_points = T::schema_entrypoints()
+            .into_iter()
+            .filter(|entry_point| entry_point.name != "init")
+            .collect();
         ContractCmd {
             name: contract_name,
+            ident: T::HostRef::ident(),
+            entry_points,
             commands,
+            caller: T::HostRef::entry_points_caller,
+        }
+    }
+
+    /// Returns the entry points of the contract, except the constructor.
+    pub fn entry_points(&self) -> &[Entrypoint] {
+        &self.entry_points
+    }
+
+    /// Returns the required args of the selected entry point which are not present in the input.
+    pub fn missing_args(&self, args: &ArgMatches, types: &CustomTypeSet) -> Vec<CommandArg> {
+        args.subcommand()
+            .and_then(|(entry_point_name, args)| {
+                self.entry_points
+                    .iter()
+                    .find(|entry_point| entry_point.name == entry_point_name)
+                    .map(|entry_point| {
+                        args::missing(
+                            args::entry_point_command_args(entry_point, args, types),
+                            args,
+                        )
+                    })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the selected entry point changes the state and is not simulated.
+    pub fn sends_deploy(&self, args: &ArgMatches) -> bool {
+        args.subcommand()
+            .and_then(|(entry_point_name, args)| {
+                self.entry_points
+                    .iter()
+                    .find(|entry_point| entry_point.name == entry_point_name)
+                    .map(|entry_point| {
+                        entry_point.is_mutable && !args::nested_flag(args, args::SIMULATE_ARG)
+                    })
+            })
+            .unwrap_or_default()
+    }
+
+    /// Checks the wasm file the contract is deployed from exists.
+    pub fn check_wasm(&self) -> Result<(), ContractError> {
+        let path = container::wasm_path(&self.ident);
+        match path.exists() {
+            true => Ok(()),
+            false => Err(ContractError::WasmNotFound(path.display().to_string())),
+        }
+    }
+
+    /// Registers the contract stored in the container in the host environment.
+    ///
+    /// A contract which is not stored in the container is skipped, it is reported
+    /// only if one of its entry points is invoked.
+    pub fn register(&self, env: &HostEnv, container: &DeployedContractsContainer) {
+        if let Some(address) = container.address(&self.name) {
+            env.register_contract(address, self.name.clone(), (self.caller)(env));
         }
     }
 }
@@ -37,16 +116,25 @@ impl OdraCommand for ContractCmd {
         &self.name
     }
 
-    fn run(&self, env: &HostEnv, args: &ArgMatches, types: &CustomTypeSet) -> Result<()> {
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
+        if !container.contains(&self.name) {
+            return Err(ContractError::NotFound(self.name.clone()).into());
+        }
         args.subcommand()
             .map(|(entrypoint_name, entrypoint_args)| {
                 self.commands
                     .iter()
                     .find(|cmd| cmd.name() == entrypoint_name)
-                    .map(|entry_point| entry_point.run(env, entrypoint_args, types))
-                    .unwrap_or(Err(anyhow::anyhow!("No entry point found")))
+                    .map(|entry_point| entry_point.run(env, container, entrypoint_args, types))
+                    .unwrap_or(Err(Error::InvalidArgs("No entry point found".to_string())))
             })
-            .unwrap_or(Err(anyhow::anyhow!("No entry point found")))
+            .unwrap_or(Err(Error::InvalidArgs("No entry point found".to_string())))
     }
 }
 
@@ -63,12 +151,32 @@ impl OdraCommand for CallCmd {
         &self.entry_point.name
     }
 
-    fn run(&self, env: &HostEnv, args: &ArgMatches, types: &CustomTypeSet) -> Result<()> {
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
         let entry_point = &self.entry_point;
         let contract_name = &self.contract_name;
 
-        let result = entry_point::call(env, contract_name, entry_point, args, types)?;
-        prettycli::info(&result);
-        Ok(())
+        if args::nested_flag(args, args::ESTIMATE_GAS_ARG) {
+            let gas =
+                entry_point::estimate_gas(env, container, contract_name, entry_point, args, types)?;
+            return Ok(CommandOutput::GasEstimate {
+                contract: contract_name.clone(),
+                entry_point: entry_point.name.clone(),
+                gas,
+            });
+        }
+
+        let output = entry_point::call(env, container, contract_name, entry_point, args, types)?;
+        Ok(CommandOutput::Call {
+            contract: contract_name.clone(),
+            entry_point: entry_point.name.clone(),
+            result: output::decoded_value(output.result, &entry_point.return_ty),
+            receipt: output.receipt,
+        })
     }
 }
@@ -0,0 +1,113 @@
+use clap::{Arg, ArgMatches, Command};
+use odra::schema::casper_contract_schema::Entrypoint;
+use serde_derive::Serialize;
+
+use crate::{
+    container::{ContainerLocation, ContractError},
+    output::CommandOutput,
+    types, DeployedContractsContainer, Error,
+};
+
+use super::{OdraCliCommand, OdraCommand};
+
+pub(crate) const CONTRACTS_INFO_SUBCOMMAND: &str = "contracts";
+const LIST_SUBCOMMAND: &str = "list";
+const SHOW_SUBCOMMAND: &str = "show";
+const NAME_ARG: &str = "name";
+
+/// An entry point of a contract as described by its schema.
+#[derive(Debug, Serialize)]
+pub(crate) struct EntryPointInfo {
+    pub name: String,
+    pub is_mutable: bool,
+    pub arguments: Vec<ArgumentInfo>,
+    pub return_type: String,
+}
+
+/// An argument of an entry point as described by the contract schema.
+#[derive(Debug, Serialize)]
+pub(crate) struct ArgumentInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub optional: bool,
+}
+
+impl From<&Entrypoint> for EntryPointInfo {
+    fn from(entry_point: &Entrypoint) -> Self {
+        Self {
+            name: entry_point.name.clone(),
+            is_mutable: entry_point.is_mutable,
+            arguments: entry_point
+                .arguments
+                .iter()
+                .map(|arg| ArgumentInfo {
+                    name: arg.name.clone(),
+                    ty: types::type_name(&arg.ty.0),
+                    optional: arg.optional,
+                })
+                .collect(),
+            return_type: types::type_name(&entry_point.return_ty.0),
+        }
+    }
+}
+
+/// Returns the command printing the contracts stored in the container.
+pub(crate) fn command() -> Command {
+    Command::new(CONTRACTS_INFO_SUBCOMMAND)
+        .about("Commands for inspecting the deployed contracts")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(Command::new(LIST_SUBCOMMAND).about("Lists the deployed contracts"))
+        .subcommand(
+            Command::new(SHOW_SUBCOMMAND)
+                .about("Prints the deployed contract and its entry points")
+                .arg(
+                    Arg::new(NAME_ARG)
+                        .help("The name or alias of the contract")
+                        .required(true),
+                ),
+        )
+}
+
+/// Runs a subcommand printing the contracts stored in the container.
+///
+/// The container is read from a file, so no host environment is needed.
+pub(crate) fn run(
+    args: &ArgMatches,
+    commands: &[OdraCliCommand],
+    location: &ContainerLocation,
+) -> Result<CommandOutput, Error> {
+    let container = DeployedContractsContainer::load(location)?;
+    match args.subcommand() {
+        Some((LIST_SUBCOMMAND, _)) => Ok(CommandOutput::ContractsList {
+            contracts: container.contracts().to_vec(),
+        }),
+        Some((SHOW_SUBCOMMAND, args)) => {
+            let name = args
+                .get_one::<String>(NAME_ARG)
+                .ok_or(Error::InvalidArgs("Missing contract name".to_string()))?;
+            let contract = container
+                .contract(name)
+                .cloned()
+                .ok_or(ContractError::NotFound(name.clone()))?;
+            let entry_points = commands
+                .iter()
+                .find_map(|cmd| match cmd {
+                    OdraCliCommand::Contract(contract) if contract.name() == name => {
+                        Some(contract.entry_points())
+                    }
+                    _ => None,
+                })
+                .unwrap_or_default()
+                .iter()
+                .map(EntryPointInfo::from)
+                .collect();
+            Ok(CommandOutput::ContractShow {
+                contract,
+                entry_points,
+            })
+        }
+        _ => Err(Error::InvalidArgs("Subcommand not found".to_string())),
+    }
+}
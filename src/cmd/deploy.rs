@@ -1,13 +1,20 @@
 use crate::{
-    container::ContractError, CustomTypeSet, DeployedContractsContainer, DEPLOY_SUBCOMMAND,
+    args,
+    container::{ContainerLocation, ContractError},
+    output::{CommandOutput, Snapshot},
+    CustomTypeSet, DeployedContractsContainer, Error, DEPLOY_SUBCOMMAND,
 };
-use anyhow::Result;
-use clap::ArgMatches;
+use clap::{Arg, ArgMatches, Command};
 use odra::{host::HostEnv, OdraError};
 use thiserror::Error;
 
 use super::OdraCommand;
 
+pub(crate) const HISTORY_SUBCOMMAND: &str = "history";
+pub(crate) const SHOW_SUBCOMMAND: &str = "show";
+pub(crate) const RESTORE_SUBCOMMAND: &str = "restore";
+const SNAPSHOT_ID_ARG: &str = "id";
+
 /// DeployCmd is a struct that represents the deploy command in the Odra CLI.
 ///
 /// The deploy command runs the [DeployScript].
@@ -20,10 +27,66 @@ impl OdraCommand for DeployCmd {
         DEPLOY_SUBCOMMAND
     }
 
-    fn run(&self, env: &HostEnv, _args: &ArgMatches, _types: &CustomTypeSet) -> Result<()> {
-        let mut container = DeployedContractsContainer::new()?;
-        self.script.deploy(env, &mut container)?;
-        Ok(())
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        _types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
+        // the default payment, the script may set its own
+        if let Some(payment) = args::payment(args) {
+            env.set_gas(payment);
+        }
+        self.script.deploy(env, container)?;
+        Ok(CommandOutput::Deploy {
+            contracts: container.contracts().to_vec(),
+        })
+    }
+}
+
+/// Returns the subcommands of the deploy command browsing the deploy history.
+pub(crate) fn history_subcommands() -> [Command; 3] {
+    let id_arg = Arg::new(SNAPSHOT_ID_ARG)
+        .help("The id of the deployment, as printed by the history command")
+        .required(true);
+    [
+        Command::new(HISTORY_SUBCOMMAND).about("Lists the previous deployments"),
+        Command::new(SHOW_SUBCOMMAND)
+            .about("Prints the contracts of a previous deployment")
+            .arg(id_arg.clone()),
+        Command::new(RESTORE_SUBCOMMAND)
+            .about("Makes a previous deployment the active one")
+            .arg(id_arg),
+    ]
+}
+
+/// Runs a subcommand of the deploy command browsing the deploy history.
+///
+/// The history is kept in files, so no host environment is needed.
+pub(crate) fn run_history(
+    args: &ArgMatches,
+    location: &ContainerLocation,
+) -> Result<CommandOutput, Error> {
+    let id = |args: &ArgMatches| {
+        args.get_one::<String>(SNAPSHOT_ID_ARG)
+            .cloned()
+            .unwrap_or_default()
+    };
+    match args.subcommand() {
+        Some((HISTORY_SUBCOMMAND, _)) => Ok(CommandOutput::DeployHistory {
+            snapshots: DeployedContractsContainer::history(location)?
+                .iter()
+                .map(Snapshot::from)
+                .collect(),
+        }),
+        Some((SHOW_SUBCOMMAND, args)) => Ok(CommandOutput::DeploySnapshot {
+            snapshot: Snapshot::from(&DeployedContractsContainer::snapshot(location, &id(args))?),
+        }),
+        Some((RESTORE_SUBCOMMAND, args)) => Ok(CommandOutput::DeployRestore {
+            snapshot: Snapshot::from(&DeployedContractsContainer::restore(location, &id(args))?),
+        }),
+        _ => Err(Error::InvalidArgs("Subcommand not found".to_string())),
     }
 }
 
@@ -31,6 +94,11 @@ impl OdraCommand for DeployCmd {
 ///
 /// In a deploy script, you can define the contracts that you want to deploy to the blockchain
 /// and write metadata to the container.
+///
+/// The container is saved each time a contract is added. If the deploy command is run
+/// with the `--resume` flag, the container holds the contracts stored by the previous run,
+/// so the script can check [DeployedContractsContainer::contains] and reuse a contract
+/// with [DeployedContractsContainer::get_ref] instead of deploying it again.
 pub trait DeployScript {
     fn deploy(
         &self,
@@ -0,0 +1,113 @@
+use std::{ops::Range, thread, time::Duration};
+
+use clap::ArgMatches;
+use odra::host::HostEnv;
+
+use crate::{
+    args,
+    events::{self, IndexedEvent},
+    output::CommandOutput,
+    CustomTypeSet, DeployedContractsContainer, Error,
+};
+
+use super::OdraCommand;
+
+pub(crate) const EVENTS_SUBCOMMAND: &str = "events";
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(5);
+
+/// EventsCmd is a struct that represents the events command in the Odra CLI.
+///
+/// The events command reads the events emitted by a deployed contract.
+pub(crate) struct EventsCmd {
+    contract_name: String,
+}
+
+impl EventsCmd {
+    pub fn new(contract_name: String) -> Self {
+        EventsCmd { contract_name }
+    }
+}
+
+impl OdraCommand for EventsCmd {
+    fn name(&self) -> &str {
+        EVENTS_SUBCOMMAND
+    }
+
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
+        let address = container
+            .address(&self.contract_name)
+            .ok_or(Error::Container(format!(
+                "Contract {} not found",
+                self.contract_name
+            )))?;
+        let from = args::events_from(args);
+        let read = |range: Range<u32>| {
+            range
+                .filter_map(|index| {
+                    let bytes = env.get_event_bytes(&address, index).ok()?;
+                    events::decode(&bytes, types).map(|event| IndexedEvent { index, event })
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if args::events_follow(args) {
+            // streams the events until the process is stopped
+            let output = args::output(args);
+            let mut next = from;
+            loop {
+                let count = env.events_count(&address);
+                read(next..count)
+                    .iter()
+                    .for_each(|event| output.print_event(&self.contract_name, event));
+                next = next.max(count);
+                thread::sleep(FOLLOW_INTERVAL);
+            }
+        }
+
+        let count = env.events_count(&address);
+        Ok(CommandOutput::Events {
+            contract: self.contract_name.clone(),
+            events: read(range(from, args::events_limit(args), count)),
+        })
+    }
+}
+
+/// Returns the indexes of the events to read, starting at `from` and bounded
+/// by the limit and the number of the emitted events.
+fn range(from: u32, limit: Option<u32>, count: u32) -> Range<u32> {
+    let to = match limit {
+        Some(limit) => count.min(from.saturating_add(limit)),
+        None => count,
+    };
+    from..to
+}
+
+#[cfg(test)]
+mod t {
+    use crate::args;
+
+    #[test]
+    fn test_range() {
+        pretty_assertions::assert_eq!(super::range(0, None, 5), 0..5);
+        pretty_assertions::assert_eq!(super::range(2, None, 5), 2..5);
+        pretty_assertions::assert_eq!(super::range(1, Some(2), 5), 1..3);
+        pretty_assertions::assert_eq!(super::range(3, Some(10), 5), 3..5);
+        pretty_assertions::assert_eq!(super::range(0, Some(0), 5), 0..0);
+        pretty_assertions::assert_eq!(super::range(u32::MAX, Some(2), 5).count(), 0);
+        pretty_assertions::assert_eq!(super::range(7, None, 5).count(), 0);
+
+        let cmd = clap::Command::new("events")
+            .arg(args::events_from_arg())
+            .arg(args::events_limit_arg())
+            .arg(args::events_follow_arg());
+        let matches = cmd.get_matches_from(["events", "--from", "1", "--limit", "2"]);
+        let range = super::range(args::events_from(&matches), args::events_limit(&matches), 5);
+        pretty_assertions::assert_eq!(range, 1..3);
+    }
+}
@@ -0,0 +1,92 @@
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
+use odra::host::HostEnv;
+
+use crate::{output::CommandOutput, CustomTypeSet, DeployedContractsContainer, Error};
+
+use super::OdraCommand;
+
+pub(crate) const IMPORT_SUBCOMMAND: &str = "import";
+const NAME_ARG: &str = "name";
+const PACKAGE_HASH_ARG: &str = "package_hash";
+const VERIFY_ARG: &str = "__verify";
+
+/// ImportCmd is a struct that represents the import command in the Odra CLI.
+///
+/// The import command stores a contract deployed by another tool in the container,
+/// so the entry points of the contract can be called. Nothing is executed, so the command
+/// does not need a host environment.
+pub(crate) struct ImportCmd;
+
+impl ImportCmd {
+    /// Returns the subcommand accepting the names of the contracts registered in the CLI.
+    pub fn subcommand(contract_names: Vec<String>) -> Command {
+        Command::new(IMPORT_SUBCOMMAND)
+            .about("Imports a contract deployed by another tool")
+            .arg(
+                Arg::new(NAME_ARG)
+                    .help("The name or alias of the contract")
+                    .required(true)
+                    .value_parser(PossibleValuesParser::new(contract_names)),
+            )
+            .arg(
+                Arg::new(PACKAGE_HASH_ARG)
+                    .help("The contract package hash, eg. hash-...")
+                    .value_name("PACKAGE_HASH")
+                    .required(true),
+            )
+            .arg(
+                Arg::new(VERIFY_ARG)
+                    .help("Checks that the contract package exists on chain")
+                    .long("verify")
+                    .required(false)
+                    .action(ArgAction::SetTrue),
+            )
+    }
+
+    /// Imports the contract to the container.
+    ///
+    /// Only a container backed by a file is accepted, a contract imported
+    /// to an in-memory container would be lost once the command completes.
+    pub fn import(
+        &self,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+    ) -> Result<CommandOutput, Error> {
+        if !container.is_livenet() {
+            return Err(Error::InvalidArgs(
+                "Importing a contract is available only on the livenet backend".to_string(),
+            ));
+        }
+        let name = args
+            .get_one::<String>(NAME_ARG)
+            .ok_or(Error::InvalidArgs("Missing contract name".to_string()))?;
+        let package_hash = args
+            .get_one::<String>(PACKAGE_HASH_ARG)
+            .ok_or(Error::InvalidArgs("Missing package hash".to_string()))?;
+        let verify = args.get_flag(VERIFY_ARG);
+
+        container.import(name, package_hash, verify)?;
+        Ok(CommandOutput::Import {
+            contract: container
+                .contract(name)
+                .cloned()
+                .ok_or(Error::Container(format!("Couldn't import {}", name)))?,
+        })
+    }
+}
+
+impl OdraCommand for ImportCmd {
+    fn name(&self) -> &str {
+        IMPORT_SUBCOMMAND
+    }
+
+    fn run(
+        &self,
+        _env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        _types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
+        self.import(container, args)
+    }
+}
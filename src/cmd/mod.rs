@@ -1,20 +1,33 @@
-use anyhow::Result;
 use clap::ArgMatches;
 use contract::ContractCmd;
 use deploy::DeployCmd;
-use odra::{host::HostEnv, schema::SchemaEntrypoints};
+use import::ImportCmd;
+use odra::{host::HostEnv, schema::SchemaEntrypoints, OdraContract};
 use scenario::ScenarioCmd;
 
-use crate::{CustomTypeSet, DeployScript, Scenario, ScenarioMetadata};
+use crate::{
+    args::CommandArg, output::CommandOutput, CustomTypeSet, DeployScript,
+    DeployedContractsContainer, Error, Scenario, ScenarioMetadata,
+};
 
+pub mod config;
 pub mod contract;
+pub mod contracts;
 pub mod deploy;
+pub mod events;
+pub mod import;
 pub mod scenario;
 
 /// OdraCommand is a trait that represents a command that can be run in the Odra CLI.
 pub(crate) trait OdraCommand {
     fn name(&self) -> &str;
-    fn run(&self, env: &HostEnv, args: &ArgMatches, types: &CustomTypeSet) -> Result<()>;
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error>;
 }
 
 /// OdraCliCommand is an enum that represents the different commands that can be run in the Odra CLI.
@@ -22,6 +35,7 @@ pub(crate) enum OdraCliCommand {
     Deploy(DeployCmd),
     Scenario(ScenarioCmd),
     Contract(ContractCmd),
+    Import(ImportCmd),
 }
 
 impl OdraCliCommand {
@@ -35,9 +49,31 @@ impl OdraCliCommand {
         OdraCliCommand::Scenario(ScenarioCmd::new(scenario))
     }
 
-    pub fn new_contract<T: SchemaEntrypoints>(contract_name: String) -> Self {
+    pub fn new_contract<T: SchemaEntrypoints + OdraContract>(contract_name: String) -> Self {
         OdraCliCommand::Contract(ContractCmd::new::<T>(contract_name))
     }
+
+    pub fn new_import() -> Self {
+        OdraCliCommand::Import(ImportCmd)
+    }
+
+    /// Returns `true` if the command sends a deploy, so it has to be signed.
+    pub fn sends_deploy(&self, args: &ArgMatches) -> bool {
+        match self {
+            OdraCliCommand::Deploy(_) | OdraCliCommand::Scenario(_) => true,
+            OdraCliCommand::Contract(contract) => contract.sends_deploy(args),
+            OdraCliCommand::Import(_) => false,
+        }
+    }
+
+    /// Returns the required args of the command which are not present in the parsed input.
+    pub fn missing_args(&self, args: &ArgMatches, types: &CustomTypeSet) -> Vec<CommandArg> {
+        match self {
+            OdraCliCommand::Scenario(scenario) => scenario.missing_args(args),
+            OdraCliCommand::Contract(contract) => contract.missing_args(args, types),
+            _ => vec![],
+        }
+    }
 }
 
 impl OdraCommand for OdraCliCommand {
@@ -46,14 +82,22 @@ impl OdraCommand for OdraCliCommand {
             OdraCliCommand::Deploy(deploy) => deploy.name(),
             OdraCliCommand::Scenario(scenario) => scenario.name(),
             OdraCliCommand::Contract(contract) => contract.name(),
+            OdraCliCommand::Import(import) => import.name(),
         }
     }
 
-    fn run(&self, env: &HostEnv, args: &ArgMatches, types: &CustomTypeSet) -> Result<()> {
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
         match self {
-            OdraCliCommand::Deploy(deploy) => deploy.run(env, args, types),
-            OdraCliCommand::Scenario(scenario) => scenario.run(env, args, types),
-            OdraCliCommand::Contract(contract) => contract.run(env, args, types),
+            OdraCliCommand::Deploy(deploy) => deploy.run(env, container, args, types),
+            OdraCliCommand::Scenario(scenario) => scenario.run(env, container, args, types),
+            OdraCliCommand::Contract(contract) => contract.run(env, container, args, types),
+            OdraCliCommand::Import(import) => import.run(env, container, args, types),
         }
     }
 }
@@ -1,9 +1,9 @@
 use std::{any::Any, collections::HashMap};
 
 use crate::{
-    args::CommandArg, container::ContractError, types, CustomTypeSet, DeployedContractsContainer,
+    args::CommandArg, container::ContractError, events, output::CommandOutput, types,
+    CustomTypeSet, DeployedContractsContainer, Error,
 };
-use anyhow::Result;
 use clap::ArgMatches;
 use odra::{casper_types::bytesrepr::FromBytes, host::HostEnv, schema::NamedCLTyped, OdraError};
 use thiserror::Error;
@@ -42,6 +42,11 @@ impl ScenarioCmd {
             scenario: Box::new(scenario),
         }
     }
+
+    /// Returns the required args of the scenario which are not present in the input.
+    pub fn missing_args(&self, args: &ArgMatches) -> Vec<CommandArg> {
+        crate::args::missing(self.scenario.args(), args)
+    }
 }
 
 impl OdraCommand for ScenarioCmd {
@@ -49,12 +54,21 @@ impl OdraCommand for ScenarioCmd {
         &self.name
     }
 
-    fn run(&self, env: &HostEnv, args: &ArgMatches, _types: &CustomTypeSet) -> Result<()> {
-        let container = DeployedContractsContainer::load()?;
-        let args = ScenarioArgs::new(self.scenario.args(), args);
-
-        self.scenario.run(env, container, args)?;
-        Ok(())
+    fn run(
+        &self,
+        env: &HostEnv,
+        container: &mut DeployedContractsContainer,
+        args: &ArgMatches,
+        types: &CustomTypeSet,
+    ) -> Result<CommandOutput, Error> {
+        let args = ScenarioArgs::new(self.scenario.args(), args)?;
+
+        let events_counts = events::counts(env, container);
+        self.scenario.run(env, container.clone(), args)?;
+        Ok(CommandOutput::Scenario {
+            name: self.name.clone(),
+            events: events::emitted_since(env, container, &events_counts, types),
+        })
     }
 }
 
@@ -83,32 +97,21 @@ impl From<OdraError> for ScenarioError {
 pub struct ScenarioArgs(HashMap<String, ScenarioArg>);
 
 impl ScenarioArgs {
-    pub(crate) fn new(args: Vec<CommandArg>, matches: &ArgMatches) -> Self {
-        let map = args
-            .into_iter()
-            .filter_map(|arg| {
-                let arg_name = &arg.name;
-                let values = matches.get_many::<String>(arg_name);
-
-                if arg.required && values.is_none() {
-                    panic!("Missing argument: {}", arg.name);
-                }
-                if values.is_none() {
-                    return None;
-                }
-                let values = values
-                    .expect("Arg not found")
-                    .map(|v| v.to_string())
-                    .collect::<Vec<_>>();
-                let scenario_arg = match arg.is_list_element {
-                    true => ScenarioArg::Many(values),
-                    false => ScenarioArg::Single(values[0].clone()),
-                };
-
-                Some((arg_name.clone(), scenario_arg))
-            })
-            .collect();
-        Self(map)
+    pub(crate) fn new(args: Vec<CommandArg>, matches: &ArgMatches) -> Result<Self, ArgError> {
+        let mut map = HashMap::new();
+        for arg in args {
+            let values = match matches.get_many::<String>(&arg.name) {
+                Some(values) => values.map(|v| v.to_string()).collect::<Vec<_>>(),
+                None if arg.required => return Err(ArgError::MissingArg(arg.name)),
+                None => continue,
+            };
+            let scenario_arg = match arg.is_list_element {
+                true => ScenarioArg::Many(values),
+                false => ScenarioArg::Single(values[0].clone()),
+            };
+            map.insert(arg.name, scenario_arg);
+        }
+        Ok(Self(map))
     }
 
     pub fn get_single<T: NamedCLTyped + FromBytes>(&self, name: &str) -> Result<T, ScenarioError> {
@@ -139,16 +142,14 @@ impl ScenarioArgs {
             .ok_or(ArgError::MissingArg(name.to_string()))?;
         match arg {
             ScenarioArg::Many(values) => values
-                .into_iter()
+                .iter()
                 .map(|value| {
-                    let bytes = types::into_bytes(&T::ty(), &value);
-                    bytes
-                        .map_err(|e| ScenarioError::TypesError(e))
-                        .and_then(|bytes| {
-                            T::from_bytes(&bytes)
-                                .map_err(|_| ScenarioError::ArgError(ArgError::Deserialization))
-                                .map(|t| t.0)
-                        })
+                    let bytes = types::into_bytes(&T::ty(), value);
+                    bytes.map_err(ScenarioError::TypesError).and_then(|bytes| {
+                        T::from_bytes(&bytes)
+                            .map_err(|_| ScenarioError::ArgError(ArgError::Deserialization))
+                            .map(|t| t.0)
+                    })
                 })
                 .collect::<Result<Vec<T>, ScenarioError>>(),
             ScenarioArg::Single(_) => Err(ScenarioError::ArgError(ArgError::ManyExpected)),
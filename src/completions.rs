@@ -0,0 +1,66 @@
+//! Completion scripts of the command tree for the supported shells.
+use std::{io::Write, path::Path};
+
+use clap::{value_parser, Arg, ArgMatches, Command};
+use clap_complete::Shell;
+
+pub(crate) const COMPLETIONS_SUBCOMMAND: &str = "completions";
+const SHELL_ARG: &str = "shell";
+
+/// Returns the command printing the completion script.
+pub(crate) fn command() -> Command {
+    Command::new(COMPLETIONS_SUBCOMMAND)
+        .about("Prints the completion script for the given shell")
+        .arg(
+            Arg::new(SHELL_ARG)
+                .help("The shell to generate the script for")
+                .required(true)
+                .value_parser(value_parser!(Shell)),
+        )
+}
+
+/// Prints the completion script of the given command to the standard output.
+///
+/// The script is generated for the name of the running binary, so it completes the contracts,
+/// the entry points and their arguments registered in this CLI.
+pub(crate) fn run(cmd: &Command, args: &ArgMatches) {
+    let Some(shell) = args.get_one::<Shell>(SHELL_ARG).copied() else {
+        return;
+    };
+    // generating straight to the stdout panics if the pipe is closed early
+    let _ = std::io::stdout().write_all(&script(cmd, shell, bin_name()));
+}
+
+fn script(cmd: &Command, shell: Shell, bin_name: String) -> Vec<u8> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd.clone(), bin_name, &mut script);
+    script
+}
+
+fn bin_name() -> String {
+    std::env::args()
+        .next()
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "odra-cli".to_string())
+}
+
+#[cfg(test)]
+mod t {
+    use clap_complete::Shell;
+
+    use crate::{test_utils::NameRegistry, OdraCli};
+
+    #[test]
+    fn test_bash_script() {
+        let cli = OdraCli::new().contract::<NameRegistry>().build();
+        let script = super::script(&cli.main_cmd, Shell::Bash, "odra-cli".to_string());
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains("odra__cli__contract__NameRegistry__register"));
+        assert!(script.contains("odra__cli__contract__NameRegistry__last_label"));
+        assert!(script.contains("--label"));
+        assert!(script.contains("odra__cli__completions"));
+    }
+}
@@ -0,0 +1,213 @@
+//! The defaults of the CLI read from the `odra-cli.toml` file.
+//!
+//! The file is placed next to the contracts container, so each network profile has its own.
+//! Each value is the default of an environment variable, so the variable and the matching
+//! flag take precedence over the file.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ArgMatches;
+use odra::casper_types::SecretKey;
+use serde_derive::Deserialize;
+use thiserror::Error;
+
+use crate::{args, container::ContainerLocation, livenet};
+
+pub(crate) const CONFIG_FILE: &str = "odra-cli.toml";
+pub(crate) const ENV_GAS: &str = "ODRA_CLI_GAS";
+pub(crate) const ENV_PAYMENT: &str = "ODRA_CLI_PAYMENT";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Couldn't read the config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid config file: {0}")]
+    TomlDeserialize(#[from] toml::de::Error),
+    #[error("{0} must be set, use the flag, the config file or the environment variable")]
+    MissingVariable(&'static str),
+    #[error("Couldn't load the secret key from {0}")]
+    SecretKey(String),
+}
+
+/// The defaults read from the config file.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Config {
+    pub node_address: Option<String>,
+    pub chain_name: Option<String>,
+    pub secret_key_path: Option<PathBuf>,
+    pub gas: Option<u64>,
+    pub payment: Option<u64>,
+}
+
+/// A value of the config, with the environment variable it is the default of.
+pub(crate) struct ConfigValue {
+    pub key: &'static str,
+    pub env: &'static str,
+    /// The flag overriding the variable, if it is accepted by every command.
+    pub flag: Option<&'static str>,
+    pub value: Option<String>,
+}
+
+impl Config {
+    /// Loads the config of the given location.
+    ///
+    /// An empty config is returned if there is no file. A relative secret key path
+    /// is resolved against the directory of the file.
+    pub fn load(location: &ContainerLocation) -> Result<Self, ConfigError> {
+        match path(location) {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let mut config: Config = toml::from_str(&fs::read_to_string(path)?)?;
+        if let (Some(key), Some(dir)) = (&mut config.secret_key_path, path.parent()) {
+            if key.is_relative() {
+                *key = dir.join(&key);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Returns the values of the config in the order they are listed in the file.
+    pub fn values(&self) -> [ConfigValue; 5] {
+        [
+            ConfigValue {
+                key: "node_address",
+                env: livenet::ENV_NODE_ADDRESS,
+                flag: Some(args::NODE_ADDRESS_ARG),
+                value: self.node_address.clone(),
+            },
+            ConfigValue {
+                key: "chain_name",
+                env: livenet::ENV_CHAIN_NAME,
+                flag: Some(args::CHAIN_NAME_ARG),
+                value: self.chain_name.clone(),
+            },
+            ConfigValue {
+                key: "secret_key_path",
+                env: livenet::ENV_SECRET_KEY_PATH,
+                flag: Some(args::SECRET_KEY_PATH_ARG),
+                value: self
+                    .secret_key_path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string()),
+            },
+            ConfigValue {
+                key: "gas",
+                env: ENV_GAS,
+                flag: None,
+                value: self.gas.map(|gas| gas.to_string()),
+            },
+            ConfigValue {
+                key: "payment",
+                env: ENV_PAYMENT,
+                flag: None,
+                value: self.payment.map(|payment| payment.to_string()),
+            },
+        ]
+    }
+
+    /// Sets the environment variables which are not set yet to the values of the config
+    /// and returns the names of the variables set.
+    ///
+    /// It must be called before the input is parsed, so the flags read the defaults.
+    pub fn apply(&self) -> Vec<&'static str> {
+        let mut applied = Vec::new();
+        for ConfigValue { env, value, .. } in self.values() {
+            if let (Some(value), None) = (value, std::env::var_os(env)) {
+                std::env::set_var(env, value);
+                applied.push(env);
+            }
+        }
+        applied
+    }
+}
+
+/// Returns the path of the config file of the given location.
+pub(crate) fn path(location: &ContainerLocation) -> Option<PathBuf> {
+    location.dir().ok().map(|dir| dir.join(CONFIG_FILE))
+}
+
+/// Loads the `.env` files read by the livenet backend, so the variables set there
+/// take precedence over the config.
+pub(crate) fn load_env_files() {
+    if let Ok(file) = std::env::var(livenet::ENV_LIVENET_ENV_FILE) {
+        dotenvy::from_filename(PathBuf::from(file).with_extension("env")).ok();
+    }
+    dotenvy::dotenv().ok();
+}
+
+/// Checks the environment variables the livenet node is reached with.
+pub(crate) fn check_livenet() -> Result<(), ConfigError> {
+    for name in [livenet::ENV_NODE_ADDRESS, livenet::ENV_CHAIN_NAME] {
+        std::env::var(name).map_err(|_| ConfigError::MissingVariable(name))?;
+    }
+    Ok(())
+}
+
+/// Checks the secret keys the livenet backend signs the deploys with can be loaded.
+///
+/// The livenet client panics if a variable is missing or a secret key can not be loaded,
+/// so the configuration is checked before the backend is built.
+pub(crate) fn check_secret_keys() -> Result<(), ConfigError> {
+    let main_key = std::env::var(livenet::ENV_SECRET_KEY_PATH)
+        .map_err(|_| ConfigError::MissingVariable(livenet::ENV_SECRET_KEY_PATH))?;
+    let account_keys =
+        (1..).map_while(|i| std::env::var(format!("{}{}", livenet::ENV_ACCOUNT_PREFIX, i)).ok());
+    for path in std::iter::once(main_key).chain(account_keys) {
+        SecretKey::from_file(&path).map_err(|_| ConfigError::SecretKey(path))?;
+    }
+    Ok(())
+}
+
+/// Sets the environment variables of the livenet backend to the values of the parsed flags.
+pub(crate) fn export(matches: &ArgMatches) {
+    for value in Config::default().values() {
+        let Some(flag) = value.flag else {
+            continue;
+        };
+        if let Some(arg) = matches.try_get_one::<String>(flag).ok().flatten() {
+            std::env::set_var(value.env, arg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use std::path::PathBuf;
+
+    use super::Config;
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join("odra_cli_test_config");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(super::CONFIG_FILE);
+        std::fs::write(
+            &path,
+            "node_address = \"http://localhost:11101\"\nsecret_key_path = \"keys/secret_key.pem\"\ngas = 5000000000\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        pretty_assertions::assert_eq!(
+            config,
+            Config {
+                node_address: Some("http://localhost:11101".to_string()),
+                chain_name: None,
+                secret_key_path: Some(dir.join(PathBuf::from("keys/secret_key.pem"))),
+                gas: Some(5_000_000_000),
+                payment: None,
+            }
+        );
+
+        std::fs::write(&path, "node = \"http://localhost:11101\"\n").unwrap();
+        assert!(Config::load_from(&path).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,15 +1,27 @@
-use std::{fs::File, io::Write, path::PathBuf, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use chrono::{DateTime, SecondsFormat, Utc};
 use odra::{
+    casper_types::crypto::blake2b,
     contract_def::HasIdent,
     host::{HostEnv, HostRef, HostRefLoader},
-    Address, OdraContract,
+    Address, DeployReport, OdraContract,
 };
+use serde::de::DeserializeOwned;
 use serde_derive::{Deserialize, Serialize};
 use thiserror::Error;
 
-const DEPLOYED_CONTRACTS_FILE: &str = "resources/deployed_contracts.toml";
+use crate::livenet;
+
+const RESOURCES_DIR: &str = "resources";
+const DEPLOYED_CONTRACTS_FILE: &str = "deployed_contracts.toml";
+const WASM_DIR: &str = "wasm";
 
 #[derive(Error, Debug)]
 pub enum ContractError {
@@ -17,10 +29,26 @@ pub enum ContractError {
     TomlSerialize(#[from] toml::ser::Error),
     #[error("Invalid TOML")]
     TomlDeserialize(#[from] toml::de::Error),
+    #[error("Invalid JSON")]
+    Json(#[from] serde_json::Error),
     #[error("Couldn't read file: {0}")]
     Io(#[from] std::io::Error),
-    #[error("Couldn't {0} find contract")]
+    #[error("Couldn't find contract {0}")]
     NotFound(String),
+    #[error("Couldn't find deploy snapshot {0}")]
+    SnapshotNotFound(String),
+    #[error("Couldn't find metadata {0}")]
+    MetadataNotFound(String),
+    #[error("Metadata {0} can't be stored in a TOML file: {1}")]
+    UnsupportedMetadata(String, toml::ser::Error),
+    #[error("Invalid contract package hash {0}")]
+    InvalidPackageHash(String),
+    #[error("Couldn't find contract package {0} on chain")]
+    PackageNotFound(String),
+    #[error(transparent)]
+    Node(#[from] livenet::NodeError),
+    #[error("Couldn't find the wasm file {0}, build the contracts first")]
+    WasmNotFound(String),
 }
 
 /// Struct representing the deployed contracts.
@@ -28,34 +56,144 @@ pub enum ContractError {
 /// This struct is used to store the contracts name and address at the deploy time
 /// and to retrieve a reference to the contract at runtime.
 ///
-/// The data is stored in a TOML file `deployed_contracts.toml` in the `{projet_root}/resources` directory.
+/// By default, the data is stored in a TOML file `deployed_contracts.toml` in the `{projet_root}/resources` directory.
+/// If a network profile is selected, the file is stored in the `{projet_root}/resources/{network}`
+/// directory, so deployments to different networks do not overwrite each other.
+/// The file can be changed with the `--contracts-file` flag or [crate::OdraCli::contracts_file],
+/// a file with the `.json` extension is stored as JSON.
+/// A container created for the in-memory backend is never written to a file.
+///
+/// Besides the contracts, the container stores metadata written by the deploy script,
+/// eg. the initial parameters of the contracts, which can be read back in scenarios.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct DeployedContractsContainer {
     time: String,
     contracts: Vec<DeployedContract>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, serde_json::Value>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
 }
 
 impl DeployedContractsContainer {
-    /// Creates a new instance.
-    pub(crate) fn new() -> Result<Self, ContractError> {
-        Self::handle_previous_version()?;
+    /// Creates a new instance at the given location.
+    pub(crate) fn new(location: &ContainerLocation) -> Result<Self, ContractError> {
+        let path = location.file_path()?;
+        Self::handle_previous_version(&path)?;
         let now: DateTime<Utc> = Utc::now();
         Ok(Self {
             time: now.to_rfc3339_opts(SecondsFormat::Secs, true),
             contracts: Vec::new(),
+            metadata: BTreeMap::new(),
+            path: Some(path),
         })
     }
 
+    /// Loads the instance at the given location to continue the previous deployment.
+    ///
+    /// If there is no previous deployment, a new instance is created.
+    pub(crate) fn resume(location: &ContainerLocation) -> Result<Self, ContractError> {
+        let path = location.file_path()?;
+        match path.exists() {
+            true => Self::load_from(&path),
+            false => Self::new(location),
+        }
+    }
+
+    /// Creates a new instance which is not backed by a file.
+    pub(crate) fn in_memory() -> Self {
+        let now: DateTime<Utc> = Utc::now();
+        Self {
+            time: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            contracts: Vec::new(),
+            metadata: BTreeMap::new(),
+            path: None,
+        }
+    }
+
     /// Adds a contract to the container.
+    ///
+    /// The contract is stored under its name, use [Self::add_contract_named]
+    /// to store multiple instances of the same contract.
     pub fn add_contract<T: HostRef + HasIdent>(
         &mut self,
         contract: &T,
     ) -> Result<(), ContractError> {
-        self.contracts
-            .push(DeployedContract::new::<T>(contract.address()));
+        self.add_contract_named(&T::ident(), contract)
+    }
+
+    /// Adds a contract to the container under the given alias.
+    ///
+    /// A contract previously stored under the same alias is replaced.
+    pub fn add_contract_named<T: HostRef + HasIdent>(
+        &mut self,
+        alias: &str,
+        contract: &T,
+    ) -> Result<(), ContractError> {
+        let deployed_contract =
+            DeployedContract::capture(alias, contract, self.is_livenet(), self.created_at());
+        self.insert(deployed_contract);
+        self.update()
+    }
+
+    /// Imports a contract deployed by another tool under the given name or alias.
+    ///
+    /// The package hash must be a formatted contract package hash, eg. `hash-...`.
+    /// If `verify` is set, the package must exist on the chain and the node must be reachable,
+    /// otherwise the details of the package are filled in only if the node responds.
+    /// The check is skipped by a container which is not backed by a file,
+    /// as it is not used with a livenet.
+    pub fn import(
+        &mut self,
+        name: &str,
+        package_hash: &str,
+        verify: bool,
+    ) -> Result<(), ContractError> {
+        let address = Address::from_str(package_hash)
+            .ok()
+            .filter(Address::is_contract)
+            .ok_or(ContractError::InvalidPackageHash(package_hash.to_string()))?;
+
+        let mut deployed_contract = DeployedContract::new(name, &address);
+        if self.is_livenet() {
+            deployed_contract.chain_name = livenet::chain_name();
+            match livenet::latest_contract_version(&address) {
+                Ok(Some(version)) => {
+                    deployed_contract.contract_hash = Some(version.contract_hash);
+                    deployed_contract.contract_version = Some(version.version);
+                }
+                Ok(None) if verify => {
+                    return Err(ContractError::PackageNotFound(package_hash.to_string()))
+                }
+                Err(err) if verify => return Err(err.into()),
+                _ => {}
+            }
+        }
+        self.insert(deployed_contract);
         self.update()
     }
 
+    /// Checks if the contracts are deployed to a livenet, only such a container is backed by a file.
+    pub(crate) fn is_livenet(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Returns the contract stored under the given name or alias.
+    pub(crate) fn contract(&self, name: &str) -> Option<&DeployedContract> {
+        self.contracts.iter().find(|c| c.name == name)
+    }
+
+    fn insert(&mut self, deployed_contract: DeployedContract) {
+        match self
+            .contracts
+            .iter_mut()
+            .find(|c| c.name == deployed_contract.name)
+        {
+            Some(c) => *c = deployed_contract,
+            None => self.contracts.push(deployed_contract),
+        }
+    }
+
     /// Gets reference to the contract.
     ///
     /// Returns a reference to the contract if it is found in the list, otherwise returns an error.
@@ -63,53 +201,170 @@ impl DeployedContractsContainer {
         &self,
         env: &HostEnv,
     ) -> Result<T::HostRef, ContractError> {
-        self.contracts
-            .iter()
-            .find(|c| c.name == T::HostRef::ident())
-            .map(|c| Address::from_str(&c.package_hash).ok())
-            .map(|opt| opt.map(|addr| <T as HostRefLoader<T::HostRef>>::load(env, addr)))
-            .flatten()
-            .ok_or(ContractError::NotFound(T::HostRef::ident()))
+        self.get_ref_named::<T>(env, &T::HostRef::ident())
+    }
+
+    /// Gets reference to the contract stored under the given alias.
+    ///
+    /// Returns a reference to the contract if it is found in the list, otherwise returns an error.
+    pub fn get_ref_named<T: OdraContract + 'static>(
+        &self,
+        env: &HostEnv,
+        alias: &str,
+    ) -> Result<T::HostRef, ContractError> {
+        self.address(alias)
+            .map(|addr| match self.path {
+                Some(_) => <T as HostRefLoader<T::HostRef>>::load(env, addr),
+                // contracts of an in-memory container live in the env they were deployed to
+                None => T::HostRef::new(addr, env.clone()),
+            })
+            .ok_or(ContractError::NotFound(alias.to_string()))
+    }
+
+    /// Returns `true` if a contract is stored under the given name or alias.
+    ///
+    /// A deploy script run with the `--resume` flag can use it to skip the contracts
+    /// deployed before the previous run failed.
+    pub fn contains(&self, name: &str) -> bool {
+        self.contract(name).is_some()
+    }
+
+    /// Stores the value under the given key in the metadata of the container.
+    ///
+    /// A value previously stored under the same key is replaced. A container stored as TOML
+    /// rejects the values TOML can not represent, eg. `None` or a number greater than `i64::MAX`.
+    pub fn set_metadata<T: serde::Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), ContractError> {
+        let value = serde_json::to_value(value).map_err(ContractError::Json)?;
+        if self.path.as_deref().is_some_and(|path| !is_json(path)) {
+            toml::Value::try_from(&value)
+                .map_err(|err| ContractError::UnsupportedMetadata(key.to_string(), err))?;
+        }
+        self.metadata.insert(key.to_string(), value);
+        self.update()
+    }
+
+    /// Gets the value stored under the given key in the metadata of the container.
+    ///
+    /// Returns an error if there is no value or it can not be deserialized into the requested type.
+    pub fn get_metadata<T: DeserializeOwned>(&self, key: &str) -> Result<T, ContractError> {
+        let value = self
+            .metadata
+            .get(key)
+            .ok_or(ContractError::MetadataNotFound(key.to_string()))?
+            .clone();
+        serde_json::from_value(value).map_err(ContractError::Json)
+    }
+
+    /// Returns the deployed contracts.
+    pub(crate) fn contracts(&self) -> &[DeployedContract] {
+        &self.contracts
     }
 
     /// Returns the contract address.
     pub fn address(&self, name: &str) -> Option<Address> {
-        self.contracts
-            .iter()
-            .find(|c| c.name == name)
-            .map(|c| Address::from_str(&c.package_hash).ok())
-            .flatten()
+        self.contract(name).and_then(DeployedContract::address)
     }
 
-    /// Load from the file.
-    pub(crate) fn load() -> Result<Self, ContractError> {
-        let path = Self::file_path()?;
+    /// Load from the file at the given location.
+    pub(crate) fn load(location: &ContainerLocation) -> Result<Self, ContractError> {
+        let path = location.file_path()?;
+        Self::load_from(&path)
+    }
+
+    /// Load from the file at the given path.
+    fn load_from(path: &Path) -> Result<Self, ContractError> {
         let file = std::fs::read_to_string(path).map_err(ContractError::Io)?;
 
-        let result = toml::from_str(&file).map_err(ContractError::TomlDeserialize)?;
+        let mut result: Self = match is_json(path) {
+            true => serde_json::from_str(&file).map_err(ContractError::Json)?,
+            false => toml::from_str(&file).map_err(ContractError::TomlDeserialize)?,
+        };
+        result.path = Some(path.to_path_buf());
         Ok(result)
     }
 
     /// Backup previous version of the file.
-    pub(crate) fn handle_previous_version() -> Result<(), ContractError> {
-        if let Ok(deployed_contracts) = Self::load() {
+    ///
+    /// The backup is a snapshot of the deploy history, identified by the creation time
+    /// of the container.
+    pub(crate) fn handle_previous_version(file_path: &Path) -> Result<(), ContractError> {
+        if let Ok(deployed_contracts) = Self::load_from(file_path) {
             // Build new file name.
             let date = deployed_contracts.time();
-            let mut path = project_root::get_project_root().map_err(ContractError::Io)?;
-            path.push(format!("{}.{}", DEPLOYED_CONTRACTS_FILE, date));
+            let path = Self::snapshot_path(file_path, date);
 
             // Store previous version under new file name.
             deployed_contracts.save_at(&path)?;
 
             // Remove old file.
-            std::fs::remove_file(path).map_err(ContractError::Io)?;
+            std::fs::remove_file(file_path).map_err(ContractError::Io)?;
         }
         Ok(())
     }
 
+    /// Returns the snapshots of the previous deployments at the given location,
+    /// sorted from the oldest.
+    pub(crate) fn history(location: &ContainerLocation) -> Result<Vec<Self>, ContractError> {
+        let path = location.file_path()?;
+        let prefix = format!("{}.", file_name(&path));
+        let dir = match path.parent().map(std::fs::read_dir) {
+            Some(Ok(dir)) => dir,
+            // nothing has been deployed yet
+            _ => return Ok(vec![]),
+        };
+
+        let mut snapshots = dir
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .filter_map(|entry| Self::load_from(&entry.path()).ok())
+            .collect::<Vec<_>>();
+        snapshots.sort_by(|a, b| a.time.cmp(&b.time));
+        Ok(snapshots)
+    }
+
+    /// Loads the snapshot with the given id from the deploy history at the given location.
+    pub(crate) fn snapshot(location: &ContainerLocation, id: &str) -> Result<Self, ContractError> {
+        // the id must not escape the resources directory
+        if id.contains(['/', '\\']) {
+            return Err(ContractError::SnapshotNotFound(id.to_string()));
+        }
+        let path = Self::snapshot_path(&location.file_path()?, id);
+        match path.exists() {
+            true => Self::load_from(&path),
+            false => Err(ContractError::SnapshotNotFound(id.to_string())),
+        }
+    }
+
+    /// Makes the snapshot with the given id the active container at the given location.
+    ///
+    /// The active container is moved to the deploy history.
+    pub(crate) fn restore(location: &ContainerLocation, id: &str) -> Result<Self, ContractError> {
+        let mut snapshot = Self::snapshot(location, id)?;
+        let path = location.file_path()?;
+        Self::handle_previous_version(&path)?;
+        snapshot.save_at(&path)?;
+        snapshot.path = Some(path);
+        Ok(snapshot)
+    }
+
+    /// Returns the id of the container in the deploy history.
+    pub(crate) fn id(&self) -> &str {
+        self.time()
+    }
+
     /// Save the file at the given path.
     fn save_at(&self, file_path: &PathBuf) -> Result<(), ContractError> {
-        let content = toml::to_string_pretty(&self).map_err(ContractError::TomlSerialize)?;
+        if let Some(dir) = file_path.parent() {
+            std::fs::create_dir_all(dir).map_err(ContractError::Io)?;
+        }
+        let content = match is_json(file_path) {
+            true => serde_json::to_string_pretty(&self).map_err(ContractError::Json)?,
+            false => toml::to_string_pretty(&self).map_err(ContractError::TomlSerialize)?,
+        };
         let mut file = File::create(file_path).map_err(ContractError::Io)?;
 
         file.write_all(content.as_bytes())
@@ -122,32 +377,433 @@ impl DeployedContractsContainer {
         &self.time
     }
 
+    fn created_at(&self) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&self.time)
+            .map(|time| time.with_timezone(&Utc))
+            .unwrap_or_default()
+    }
+
     /// Update the file.
     fn update(&self) -> Result<(), ContractError> {
-        let path = Self::file_path()?;
-        self.save_at(&path)
+        match &self.path {
+            Some(path) => self.save_at(path),
+            None => Ok(()),
+        }
+    }
+
+    fn snapshot_path(file_path: &Path, id: &str) -> PathBuf {
+        file_path.with_file_name(format!("{}.{}", file_name(file_path), id))
     }
+}
 
-    fn file_path() -> Result<PathBuf, ContractError> {
-        let mut path = project_root::get_project_root().map_err(ContractError::Io)?;
-        path.push(DEPLOYED_CONTRACTS_FILE);
+/// The location of the container file of a network profile.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ContainerLocation {
+    file: Option<PathBuf>,
+    network: Option<String>,
+}
+
+impl ContainerLocation {
+    /// Creates a location of the given file, or the default file if not set.
+    pub fn new(file: Option<PathBuf>, network: Option<&str>) -> Self {
+        Self {
+            file,
+            network: network.map(str::to_string),
+        }
+    }
+
+    /// Returns the directory of the container file.
+    ///
+    /// The default file is resolved against the project root. The directory of a network
+    /// profile is placed next to the file.
+    pub(crate) fn dir(&self) -> Result<PathBuf, ContractError> {
+        let mut path = match &self.file {
+            Some(file) => file.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => {
+                let mut path = project_root::get_project_root().map_err(ContractError::Io)?;
+                path.push(RESOURCES_DIR);
+                path
+            }
+        };
+        if let Some(network) = &self.network {
+            path.push(network);
+        }
+        Ok(path)
+    }
+
+    /// Returns the path of the container file.
+    fn file_path(&self) -> Result<PathBuf, ContractError> {
+        let mut path = self.dir()?;
+        match self.file.as_ref().and_then(|file| file.file_name()) {
+            Some(file_name) => path.push(file_name),
+            None => path.push(DEPLOYED_CONTRACTS_FILE),
+        }
 
         Ok(path)
     }
 }
 
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Checks if the file is stored as JSON.
+///
+/// The snapshots of the deploy history keep the extension of the file, followed by their id.
+fn is_json(path: &Path) -> bool {
+    file_name(path).split('.').skip(1).any(|ext| ext == "json")
+}
+
 /// This struct represents a contract in the `deployed_contracts.toml` file.
+///
+/// The details of the deployment are optional, so the files written before they were recorded
+/// can still be loaded. A detail is unknown if the backend does not report it.
 #[derive(Deserialize, Serialize, Debug, Clone)]
-struct DeployedContract {
+pub(crate) struct DeployedContract {
     name: String,
     package_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contract_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contract_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deploy_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deployer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gas: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wasm_checksum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp: Option<String>,
 }
 
 impl DeployedContract {
-    fn new<T: HasIdent>(address: &Address) -> Self {
+    fn new(name: &str, address: &Address) -> Self {
         Self {
-            name: T::ident(),
+            name: name.to_string(),
             package_hash: address.to_string(),
+            contract_hash: None,
+            contract_version: None,
+            deploy_hash: None,
+            deployer: None,
+            gas: None,
+            chain_name: None,
+            wasm_checksum: None,
+            timestamp: None,
+        }
+    }
+
+    /// Captures the details of the deployment of the given contract.
+    ///
+    /// The on-chain details are read from the node only if the contract is deployed to a livenet,
+    /// the deploy installing the contract is searched for among the deploys sent since the given time.
+    /// The details the node does not report are left empty.
+    fn capture<T: HostRef + HasIdent>(
+        name: &str,
+        contract: &T,
+        is_livenet: bool,
+        since: DateTime<Utc>,
+    ) -> Self {
+        let env = contract.env();
+        let ident = T::ident();
+        let mut deployed_contract = Self::new(name, contract.address());
+        deployed_contract.deployer = Some(env.caller().to_string());
+        deployed_contract.wasm_checksum = wasm_checksum(&ident);
+        deployed_contract.timestamp = Some(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+        if is_livenet {
+            deployed_contract.chain_name = livenet::chain_name();
+            let package_hash = deployed_contract.package_hash.clone();
+            let deploy = livenet::find_deploy(&env.caller(), since, |deploy| {
+                deploy.package_hash.as_ref() == Some(&package_hash)
+            });
+            if let Some(deploy) = deploy {
+                deployed_contract.deploy_hash = Some(deploy.deploy_hash);
+                deployed_contract.gas = deploy.cost;
+            }
+            // the package may have been upgraded, so its latest version is recorded
+            if let Ok(Some(version)) = livenet::latest_contract_version(contract.address()) {
+                deployed_contract.contract_hash = Some(version.contract_hash);
+                deployed_contract.contract_version = Some(version.version);
+            }
+        } else {
+            deployed_contract.gas = wasm_deploy_gas(env, &ident);
         }
+        deployed_contract
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn address(&self) -> Option<Address> {
+        Address::from_str(&self.package_hash).ok()
+    }
+
+    pub fn package_hash(&self) -> &str {
+        &self.package_hash
+    }
+
+    pub fn timestamp(&self) -> Option<&str> {
+        self.timestamp.as_deref()
+    }
+}
+
+/// Returns the path of the wasm file of the contract, relative to the working directory.
+pub(crate) fn wasm_path(contract_name: &str) -> PathBuf {
+    PathBuf::from(WASM_DIR)
+        .join(contract_name)
+        .with_extension("wasm")
+}
+
+/// Returns the hex encoded blake2b hash of the wasm file of the contract.
+fn wasm_checksum(contract_name: &str) -> Option<String> {
+    std::fs::read(wasm_path(contract_name))
+        .ok()
+        .map(|wasm| hex::encode(blake2b(wasm)))
+}
+
+/// Returns the gas spent on the last deployment of the contract wasm reported by the backend.
+fn wasm_deploy_gas(env: &HostEnv, contract_name: &str) -> Option<u64> {
+    let file_name = format!("{}.wasm", contract_name);
+    env.gas_report()
+        .into_iter()
+        .filter_map(|report| match report {
+            DeployReport::WasmDeploy { gas, file_name: f } if f.ends_with(&file_name) => {
+                Some(gas.as_u64())
+            }
+            _ => None,
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod t {
+    use std::path::PathBuf;
+
+    use super::{ContainerLocation, DeployedContract, DeployedContractsContainer};
+
+    #[test]
+    fn test_load_two_field_records() {
+        let file = r#"
+            time = "2024-05-01T10:00:00Z"
+
+            [[contracts]]
+            name = "Erc20"
+            package_hash = "hash-0000000000000000000000000000000000000000000000000000000000000001"
+        "#;
+        let container: DeployedContractsContainer = toml::from_str(file).unwrap();
+        let contract = &container.contracts()[0];
+        pretty_assertions::assert_eq!(contract.name(), "Erc20");
+        pretty_assertions::assert_eq!(contract.deployer, None);
+        assert!(container.address("Erc20").is_some());
+    }
+
+    #[test]
+    fn test_metadata() {
+        #[derive(serde_derive::Serialize, serde_derive::Deserialize, Debug, PartialEq)]
+        struct Params {
+            symbol: String,
+            decimals: u8,
+        }
+        let params = Params {
+            symbol: "USDT".to_string(),
+            decimals: 6,
+        };
+
+        let mut container = DeployedContractsContainer::in_memory();
+        container.set_metadata("params", &params).unwrap();
+        container
+            .set_metadata("admins", &vec!["alice", "bob"])
+            .unwrap();
+
+        let file = toml::to_string_pretty(&container).unwrap();
+        let container: DeployedContractsContainer = toml::from_str(&file).unwrap();
+        pretty_assertions::assert_eq!(container.get_metadata::<Params>("params").unwrap(), params);
+        pretty_assertions::assert_eq!(
+            container.get_metadata::<Vec<String>>("admins").unwrap(),
+            vec!["alice", "bob"]
+        );
+        assert!(container.get_metadata::<u8>("params").is_err());
+        assert!(container.get_metadata::<u8>("owner").is_err());
+
+        // JSON keeps the values TOML can not represent
+        let mut container = DeployedContractsContainer::in_memory();
+        container.set_metadata("supply", &u64::MAX).unwrap();
+        container.set_metadata("owner", &None::<String>).unwrap();
+        let file = serde_json::to_string_pretty(&container).unwrap();
+        let container: DeployedContractsContainer = serde_json::from_str(&file).unwrap();
+        pretty_assertions::assert_eq!(container.get_metadata::<u64>("supply").unwrap(), u64::MAX);
+        pretty_assertions::assert_eq!(
+            container.get_metadata::<Option<String>>("owner").unwrap(),
+            None
+        );
+
+        let dir = std::env::temp_dir().join("odra_cli_test_metadata");
+        let location = ContainerLocation::new(Some(dir.join("contracts.toml")), None);
+        let mut container = DeployedContractsContainer::new(&location).unwrap();
+        assert!(container.set_metadata("supply", &u64::MAX).is_err());
+        assert!(container.set_metadata("owner", &None::<String>).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = std::env::temp_dir().join("odra_cli_test_history");
+        std::fs::remove_dir_all(&dir).ok();
+        let location = ContainerLocation::new(Some(dir.join("contracts.toml")), None);
+        let address = "hash-0000000000000000000000000000000000000000000000000000000000000001"
+            .parse()
+            .unwrap();
+        let deploy = |time: &str, name: &str| {
+            let mut container = DeployedContractsContainer::new(&location).unwrap();
+            container.time = time.to_string();
+            container.insert(DeployedContract::new(name, &address));
+            container.update().unwrap();
+        };
+        deploy("2024-05-01T10:00:00Z", "Erc20");
+        deploy("2024-05-02T10:00:00Z", "Erc721");
+        deploy("2024-05-03T10:00:00Z", "Erc1155");
+
+        let ids = |snapshots: Vec<DeployedContractsContainer>| {
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.id().to_string())
+                .collect::<Vec<_>>()
+        };
+        let history = DeployedContractsContainer::history(&location).unwrap();
+        pretty_assertions::assert_eq!(
+            ids(history),
+            vec!["2024-05-01T10:00:00Z", "2024-05-02T10:00:00Z"]
+        );
+
+        let snapshot = DeployedContractsContainer::snapshot(&location, "2024-05-01T10:00:00Z");
+        assert!(snapshot.unwrap().contains("Erc20"));
+        assert!(DeployedContractsContainer::snapshot(&location, "2024-05-04T10:00:00Z").is_err());
+        assert!(DeployedContractsContainer::snapshot(&location, "../contracts.toml").is_err());
+
+        // the active container is moved to the history
+        DeployedContractsContainer::restore(&location, "2024-05-01T10:00:00Z").unwrap();
+        let active = DeployedContractsContainer::load(&location).unwrap();
+        pretty_assertions::assert_eq!(active.id(), "2024-05-01T10:00:00Z");
+        assert!(active.contains("Erc20"));
+        let history = DeployedContractsContainer::history(&location).unwrap();
+        pretty_assertions::assert_eq!(
+            ids(history),
+            vec![
+                "2024-05-01T10:00:00Z",
+                "2024-05-02T10:00:00Z",
+                "2024-05-03T10:00:00Z"
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_json() {
+        assert!(super::is_json(&PathBuf::from("contracts.json")));
+        assert!(super::is_json(&PathBuf::from(
+            "resources/contracts.json.2024-05-01T10:00:00Z"
+        )));
+        assert!(!super::is_json(&PathBuf::from("deployed_contracts.toml")));
+        assert!(!super::is_json(&PathBuf::from("json/contracts.toml")));
+
+        let dir = std::env::temp_dir().join("odra_cli_test_json");
+        std::fs::remove_dir_all(&dir).ok();
+        let location = ContainerLocation::new(Some(dir.join("contracts.json")), None);
+        let mut container = DeployedContractsContainer::new(&location).unwrap();
+        let address = "hash-0000000000000000000000000000000000000000000000000000000000000001";
+        let mut contract = DeployedContract::new("Erc20", &address.parse().unwrap());
+        contract.contract_hash = Some(
+            "contract-0000000000000000000000000000000000000000000000000000000000000002".to_string(),
+        );
+        contract.contract_version = Some(1);
+        contract.deploy_hash =
+            Some("0000000000000000000000000000000000000000000000000000000000000003".to_string());
+        contract.deployer = Some(
+            "account-hash-0000000000000000000000000000000000000000000000000000000000000004"
+                .to_string(),
+        );
+        contract.gas = Some(150_000_000_000);
+        contract.chain_name = Some("casper-test".to_string());
+        contract.wasm_checksum = Some("00".repeat(32));
+        contract.timestamp = Some("2024-05-01T10:00:00Z".to_string());
+        container.insert(contract);
+        container.insert(DeployedContract::new("Erc721", &address.parse().unwrap()));
+        container.set_metadata("supply", &u64::MAX).unwrap();
+
+        let file = std::fs::read_to_string(dir.join("contracts.json")).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&file).unwrap();
+        // the optional fields which are not set are skipped
+        pretty_assertions::assert_eq!(
+            stored["contracts"][1],
+            serde_json::json!({ "name": "Erc721", "package_hash": address })
+        );
+
+        let loaded = DeployedContractsContainer::load(&location).unwrap();
+        pretty_assertions::assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&container).unwrap()
+        );
+        pretty_assertions::assert_eq!(loaded.contracts()[0].gas, Some(150_000_000_000));
+        pretty_assertions::assert_eq!(loaded.get_metadata::<u64>("supply").unwrap(), u64::MAX);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_import() {
+        let package_hash = "hash-0000000000000000000000000000000000000000000000000000000000000001";
+        let mut container = DeployedContractsContainer::in_memory();
+        container.import("Erc20", package_hash, true).unwrap();
+        pretty_assertions::assert_eq!(
+            container
+                .address("Erc20")
+                .map(|address| address.to_string()),
+            Some(package_hash.to_string())
+        );
+
+        let account =
+            "account-hash-0000000000000000000000000000000000000000000000000000000000000001";
+        assert!(container.import("Erc20", account, false).is_err());
+        assert!(container.import("Erc20", "hash-1", false).is_err());
+    }
+
+    #[test]
+    fn test_location() {
+        let resources = project_root::get_project_root().unwrap().join("resources");
+        pretty_assertions::assert_eq!(
+            ContainerLocation::new(None, None).file_path().unwrap(),
+            resources.join("deployed_contracts.toml")
+        );
+        pretty_assertions::assert_eq!(
+            ContainerLocation::new(None, Some("testnet"))
+                .file_path()
+                .unwrap(),
+            resources.join("testnet").join("deployed_contracts.toml")
+        );
+
+        let file = PathBuf::from("/deployments/contracts.json");
+        pretty_assertions::assert_eq!(
+            ContainerLocation::new(Some(file.clone()), None)
+                .file_path()
+                .unwrap(),
+            file
+        );
+        pretty_assertions::assert_eq!(
+            ContainerLocation::new(Some(file), Some("mainnet"))
+                .file_path()
+                .unwrap(),
+            PathBuf::from("/deployments/mainnet/contracts.json")
+        );
+        pretty_assertions::assert_eq!(
+            ContainerLocation::new(Some(PathBuf::from("contracts.toml")), Some("testnet"))
+                .file_path()
+                .unwrap(),
+            PathBuf::from("testnet/contracts.toml")
+        );
     }
 }
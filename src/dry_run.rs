@@ -0,0 +1,425 @@
+//! A host environment executing the entry points locally against the state of a live network.
+//!
+//! Nothing is signed or sent to the node, so no secret key is needed. The state is read
+//! from the node, the changes made by a call are kept in memory and discarded once
+//! the command completes. The events emitted and the tokens transferred by a call are dropped.
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    sync::RwLock,
+};
+
+use odra::{
+    casper_types::{
+        account::AccountHash, bytesrepr::Bytes, crypto, CLValue, PublicKey, RuntimeArgs, SecretKey,
+        U512,
+    },
+    entry_point_callback::EntryPointsCaller,
+    host::{HostContext, HostEnv},
+    Address, CallDef, ContractContext, ContractEnv, EventError, ExecutionError, GasReport,
+    OdraError, OdraResult,
+};
+use odra_casper_rpc_client::casper_client::{
+    configuration::CasperClientConfiguration, CasperClient,
+};
+use odra_core::{
+    callstack::{Callstack, CallstackElement},
+    ContractContainer, ContractRegister,
+};
+use tokio::runtime::Runtime;
+
+use crate::livenet;
+
+/// Builds a host environment reading the state of the node configured by the livenet
+/// environment variables.
+///
+/// The calls are made by the account of the secret key, if it is configured.
+pub(crate) fn env() -> HostEnv {
+    let var = |name| std::env::var(name).unwrap_or_default();
+    let client = CasperClient::new(CasperClientConfiguration {
+        node_address: var(livenet::ENV_NODE_ADDRESS),
+        rpc_id: "1".to_string(),
+        chain_name: var(livenet::ENV_CHAIN_NAME),
+        secret_keys: vec![],
+        secret_key_paths: vec![],
+        cspr_cloud_auth_token: std::env::var(livenet::ENV_CSPR_CLOUD_AUTH_TOKEN).ok(),
+    });
+    let caller = std::env::var(livenet::ENV_SECRET_KEY_PATH)
+        .ok()
+        .and_then(|path| SecretKey::from_file(path).ok())
+        .map(|key| Address::from(PublicKey::from(&key)))
+        .unwrap_or(Address::Account(AccountHash::new([0; 32])));
+    HostEnv::new(Rc::new(RefCell::new(DryRunHost::new(client, caller))))
+}
+
+struct DryRunHost {
+    state: Rc<State>,
+    contract_env: Rc<ContractEnv>,
+    caller: RefCell<Address>,
+}
+
+impl DryRunHost {
+    fn new(client: CasperClient, caller: Address) -> Self {
+        let state = Rc::new(State {
+            client,
+            runtime: Runtime::new().expect("Couldn't create tokio runtime"),
+            callstack: Default::default(),
+            register: Default::default(),
+            changes: Default::default(),
+            removed_dictionaries: Default::default(),
+        });
+        let context = Rc::new(RefCell::new(DryRunContext(state.clone())));
+        Self {
+            state,
+            contract_env: Rc::new(ContractEnv::new(0, context)),
+            caller: RefCell::new(caller),
+        }
+    }
+}
+
+impl HostContext for DryRunHost {
+    fn set_caller(&self, caller: Address) {
+        self.caller.replace(caller);
+    }
+
+    fn set_gas(&self, _gas: u64) {}
+
+    fn caller(&self) -> Address {
+        *self.caller.borrow()
+    }
+
+    fn get_account(&self, index: usize) -> Address {
+        match index {
+            0 => self.caller(),
+            _ => panic!("Only the account of the secret key is available in a dry run"),
+        }
+    }
+
+    fn balance_of(&self, address: &Address) -> U512 {
+        let state = &self.state;
+        state.runtime.block_on(state.client.get_balance(address))
+    }
+
+    fn advance_block_time(&self, _time_diff: u64) {
+        panic!("Cannot advance the block time in a dry run")
+    }
+
+    fn block_time(&self) -> u64 {
+        let state = &self.state;
+        state.runtime.block_on(state.client.get_block_time())
+    }
+
+    fn get_event(&self, contract_address: &Address, index: u32) -> Result<Bytes, EventError> {
+        let state = &self.state;
+        state
+            .runtime
+            .block_on(state.client.get_event(contract_address, index))
+            .map_err(|_| EventError::CouldntExtractEventData)
+    }
+
+    fn get_events_count(&self, contract_address: &Address) -> u32 {
+        let state = &self.state;
+        state
+            .runtime
+            .block_on(state.client.events_count(contract_address))
+            .unwrap_or_default()
+    }
+
+    fn call_contract(
+        &self,
+        address: &Address,
+        call_def: CallDef,
+        _use_proxy: bool,
+    ) -> OdraResult<Bytes> {
+        let callstack = &self.state.callstack;
+        callstack
+            .borrow_mut()
+            .push(CallstackElement::new_account(self.caller()));
+        let result = self.state.call(*address, call_def);
+        callstack.borrow_mut().pop();
+        result
+    }
+
+    fn new_contract(
+        &self,
+        _name: &str,
+        _init_args: RuntimeArgs,
+        _entry_points_caller: EntryPointsCaller,
+    ) -> OdraResult<Address> {
+        panic!("Cannot deploy a contract in a dry run")
+    }
+
+    fn register_contract(
+        &self,
+        address: Address,
+        _contract_name: String,
+        entry_points_caller: EntryPointsCaller,
+    ) {
+        self.state
+            .register
+            .write()
+            .expect("Couldn't write contract register")
+            .add(address, ContractContainer::new(entry_points_caller));
+    }
+
+    fn contract_env(&self) -> ContractEnv {
+        (*self.contract_env).clone()
+    }
+
+    fn gas_report(&self) -> GasReport {
+        GasReport::default()
+    }
+
+    fn last_call_gas_cost(&self) -> u64 {
+        0
+    }
+
+    fn sign_message(&self, _message: &Bytes, _address: &Address) -> Bytes {
+        panic!("Cannot sign a message in a dry run")
+    }
+
+    fn public_key(&self, _address: &Address) -> PublicKey {
+        panic!("Cannot read a public key in a dry run")
+    }
+
+    fn transfer(&self, _to: Address, _amount: U512) -> OdraResult<()> {
+        panic!("Cannot transfer tokens in a dry run")
+    }
+}
+
+/// A key of the contract storage changed by a call.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum StorageKey {
+    Value(Vec<u8>),
+    Named(String),
+    Dictionary(String, Vec<u8>),
+}
+
+/// The state shared by the host and the contract context.
+struct State {
+    client: CasperClient,
+    runtime: Runtime,
+    callstack: RefCell<Callstack>,
+    register: RwLock<ContractRegister>,
+    changes: RefCell<BTreeMap<(Address, StorageKey), Bytes>>,
+    removed_dictionaries: RefCell<BTreeSet<(Address, String)>>,
+}
+
+impl State {
+    fn call(&self, address: Address, call_def: CallDef) -> OdraResult<Bytes> {
+        self.callstack
+            .borrow_mut()
+            .push(CallstackElement::new_contract_call(
+                address,
+                call_def.clone(),
+            ));
+        let result = self
+            .register
+            .read()
+            .expect("Couldn't read contract register")
+            .call(&address, call_def);
+        self.callstack.borrow_mut().pop();
+        result
+    }
+
+    fn current_address(&self) -> Address {
+        *self.callstack.borrow().current().address()
+    }
+
+    fn change(&self, key: StorageKey) -> Option<Bytes> {
+        let address = self.current_address();
+        self.changes.borrow().get(&(address, key)).cloned()
+    }
+
+    fn set(&self, key: StorageKey, value: Bytes) {
+        let address = self.current_address();
+        self.changes.borrow_mut().insert((address, key), value);
+    }
+}
+
+struct DryRunContext(Rc<State>);
+
+impl ContractContext for DryRunContext {
+    fn get_value(&self, key: &[u8]) -> Option<Bytes> {
+        let state = &self.0;
+        state.change(StorageKey::Value(key.to_vec())).or_else(|| {
+            let address = state.current_address();
+            state
+                .runtime
+                .block_on(state.client.get_value(&address, key))
+                .ok()
+        })
+    }
+
+    fn set_value(&self, key: &[u8], value: Bytes) {
+        self.0.set(StorageKey::Value(key.to_vec()), value);
+    }
+
+    fn get_named_value(&self, name: &str) -> Option<Bytes> {
+        let state = &self.0;
+        state
+            .change(StorageKey::Named(name.to_string()))
+            .or_else(|| {
+                let address = state.current_address();
+                state
+                    .runtime
+                    .block_on(state.client.get_named_value(&address, name))
+            })
+    }
+
+    fn set_named_value(&self, name: &str, value: CLValue) {
+        let value = Bytes::from(value.inner_bytes().as_slice());
+        self.0.set(StorageKey::Named(name.to_string()), value);
+    }
+
+    fn get_dictionary_value(&self, dictionary_name: &str, key: &[u8]) -> Option<Bytes> {
+        let state = &self.0;
+        let address = state.current_address();
+        let dictionary = StorageKey::Dictionary(dictionary_name.to_string(), key.to_vec());
+        if let Some(value) = state.change(dictionary) {
+            return Some(value);
+        }
+        let is_removed = state
+            .removed_dictionaries
+            .borrow()
+            .contains(&(address, dictionary_name.to_string()));
+        match is_removed {
+            true => None,
+            false => state.runtime.block_on(state.client.get_dictionary_value(
+                &address,
+                dictionary_name,
+                key,
+            )),
+        }
+    }
+
+    fn set_dictionary_value(&self, dictionary_name: &str, key: &[u8], value: CLValue) {
+        let value = Bytes::from(value.inner_bytes().as_slice());
+        let key = StorageKey::Dictionary(dictionary_name.to_string(), key.to_vec());
+        self.0.set(key, value);
+    }
+
+    fn remove_dictionary(&self, dictionary_name: &str) {
+        let state = &self.0;
+        let address = state.current_address();
+        state.changes.borrow_mut().retain(|(changed, key), _| {
+            *changed != address
+                || !matches!(key, StorageKey::Dictionary(name, _) if name == dictionary_name)
+        });
+        state
+            .removed_dictionaries
+            .borrow_mut()
+            .insert((address, dictionary_name.to_string()));
+    }
+
+    fn caller(&self) -> Address {
+        *self.0.callstack.borrow().previous().address()
+    }
+
+    fn self_address(&self) -> Address {
+        self.0.current_address()
+    }
+
+    fn call_contract(&self, address: Address, call_def: CallDef) -> Bytes {
+        self.0
+            .call(address, call_def)
+            .unwrap_or_else(|error| self.revert(error))
+    }
+
+    fn get_block_time(&self) -> u64 {
+        let state = &self.0;
+        state.runtime.block_on(state.client.get_block_time())
+    }
+
+    fn attached_value(&self) -> U512 {
+        self.0.callstack.borrow().attached_value()
+    }
+
+    fn self_balance(&self) -> U512 {
+        let state = &self.0;
+        let address = state.current_address();
+        state.runtime.block_on(state.client.get_balance(&address))
+    }
+
+    fn emit_event(&self, _event: &Bytes) {}
+
+    fn transfer_tokens(&self, _to: &Address, _amount: &U512) {}
+
+    fn revert(&self, error: OdraError) -> ! {
+        let mut revert_msg = String::new();
+        if let CallstackElement::ContractCall { address, call_def } =
+            self.0.callstack.borrow().current()
+        {
+            revert_msg = format!("{:?}::{}", address, call_def.entry_point());
+        }
+        panic!("Revert: {:?} - {}", error, revert_msg);
+    }
+
+    fn get_named_arg_bytes(&self, name: &str) -> OdraResult<Bytes> {
+        self.get_opt_named_arg_bytes(name)
+            .ok_or(OdraError::ExecutionError(ExecutionError::MissingArg))
+    }
+
+    fn get_opt_named_arg_bytes(&self, name: &str) -> Option<Bytes> {
+        match self.0.callstack.borrow().current() {
+            CallstackElement::Account(_) => None,
+            CallstackElement::ContractCall { call_def, .. } => call_def
+                .args()
+                .get(name)
+                .map(|value| Bytes::from(value.inner_bytes().as_slice())),
+        }
+    }
+
+    fn handle_attached_value(&self) {}
+
+    fn clear_attached_value(&self) {}
+
+    fn hash(&self, bytes: &[u8]) -> [u8; 32] {
+        crypto::blake2b(bytes)
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use std::{cell::RefCell, rc::Rc};
+
+    use odra::{
+        casper_types::{account::AccountHash, bytesrepr::FromBytes, runtime_args, RuntimeArgs},
+        host::{EntryPointsCallerProvider, HostContext, HostEnv},
+        Address, CallDef, OdraContract,
+    };
+    use odra_casper_rpc_client::casper_client::{
+        configuration::CasperClientConfiguration, CasperClient,
+    };
+
+    use crate::test_utils::NameRegistry;
+
+    #[test]
+    fn test_dry_run_changes() {
+        // the node is never reached, the value is read back from the changes
+        let client = CasperClient::new(CasperClientConfiguration {
+            node_address: "http://127.0.0.1:1".to_string(),
+            rpc_id: "1".to_string(),
+            chain_name: "test".to_string(),
+            secret_keys: vec![],
+            secret_key_paths: vec![],
+            cspr_cloud_auth_token: None,
+        });
+        let caller = Address::Account(AccountHash::new([1; 32]));
+        let host = Rc::new(RefCell::new(super::DryRunHost::new(client, caller)));
+        let env = HostEnv::new(host.clone());
+        let address = Address::Account(AccountHash::new([2; 32]));
+        let entry_points_caller =
+            <NameRegistry as OdraContract>::HostRef::entry_points_caller(&env);
+        let host = host.borrow();
+        host.register_contract(address, "NameRegistry".to_string(), entry_points_caller);
+
+        let register = CallDef::new("register", true, runtime_args! { "label" => "alice" });
+        host.call_contract(&address, register, false).unwrap();
+        let last_label = CallDef::new("last_label", false, runtime_args! {});
+        let bytes = host.call_contract(&address, last_label, false).unwrap();
+        let (label, _) = Option::<String>::from_bytes(&bytes).unwrap();
+        pretty_assertions::assert_eq!(label, Some("alice".to_string()));
+    }
+}
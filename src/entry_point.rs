@@ -1,3 +1,4 @@
+use chrono::Utc;
 use clap::ArgMatches;
 use odra::{
     casper_types::U512,
@@ -5,11 +6,44 @@ use odra::{
     schema::casper_contract_schema::{Entrypoint, NamedCLType},
     CallDef,
 };
+use serde_derive::Serialize;
 
-use crate::{args, container, types, CustomTypeSet, DeployedContractsContainer};
+use crate::{
+    args, container,
+    events::{self, ContractEvents},
+    livenet, types, CustomTypeSet, DeployedContractsContainer,
+};
 
 pub const DEFAULT_GAS: u64 = 20_000_000_000;
 
+/// The result of an entry point call.
+pub struct CallOutput {
+    /// The decoded return value.
+    pub result: String,
+    /// The receipt of the call, present only if the call changed the state.
+    pub receipt: Option<Receipt>,
+}
+
+/// Details of an executed mutable call, collected only if requested.
+///
+/// The deploy the call was sent in is known only on a livenet, where it is found on the chain.
+/// A detail not reported by the backend is omitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct Receipt {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deploy_hash: Option<String>,
+    pub caller: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    pub attached_value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_time: Option<String>,
+    /// The events emitted by the contracts in the container, like in a scenario.
+    pub events: Vec<ContractEvents>,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CallError {
     #[error("Execution error: {0}")]
@@ -24,14 +58,22 @@ pub enum CallError {
     ContractError(#[from] container::ContractError),
 }
 
+/// Calls the entry point of a deployed contract and returns the decoded result.
+///
+/// Non-mutable entry points, and mutable ones called with `--simulate`, are executed
+/// without a deploy - no deploy is signed, no gas is set and no receipt is attached.
+/// The changes made by a simulated call are not rolled back, so it is run on a host environment
+/// discarded once the command completes, see [crate::backend::Backend::dry_run_env].
+/// A receipt is attached to the result of any other call if it is requested with `--receipt`,
+/// or the gas is estimated.
 pub fn call(
     env: &HostEnv,
+    container: &DeployedContractsContainer,
     contract_name: &str,
     entry_point: &Entrypoint,
     args: &ArgMatches,
     types: &CustomTypeSet,
-) -> Result<String, CallError> {
-    let container = DeployedContractsContainer::load()?;
+) -> Result<CallOutput, CallError> {
     let amount = args
         .try_get_one::<String>("__attached_value")
         .ok()
@@ -39,23 +81,76 @@ pub fn call(
         .map(|s| U512::from_dec_str(s).map_err(|_| types::Error::SerializationError))
         .unwrap_or(Ok(U512::zero()))?;
 
-    let runtime_args = args::compose(&entry_point, args, types)?;
+    let runtime_args = args::compose(entry_point, args, types)?;
     let contract_address = container
         .address(contract_name)
         .ok_or(CallError::ContractNotFound)?;
 
     let method = &entry_point.name;
-    let is_mut = entry_point.is_mutable;
+    let is_mut = entry_point.is_mutable && !args::nested_flag(args, args::SIMULATE_ARG);
     let ty = &entry_point.return_ty;
     let call_def = CallDef::new(method, is_mut, runtime_args).with_amount(amount);
-    let use_proxy = ty.0 != NamedCLType::Unit || !call_def.amount().is_zero();
+    let use_proxy = is_mut && (ty.0 != NamedCLType::Unit || !call_def.amount().is_zero());
 
+    let with_receipt = is_mut
+        && (args::nested_flag(args, args::RECEIPT_ARG)
+            || args::nested_flag(args, args::ESTIMATE_GAS_ARG));
+    // the events of the contracts called by the callee are listed as well
+    let events_counts = with_receipt.then(|| events::counts(env, container));
     if is_mut {
-        env.set_gas(DEFAULT_GAS);
+        env.set_gas(args::gas(args).unwrap_or(DEFAULT_GAS));
     }
+    let sent_at = Utc::now();
+    let sent_call = call_def.clone();
     let bytes = env
         .raw_call_contract(contract_address, call_def, use_proxy)
         .map_err(|e| CallError::ExecutionError(format!("{:?}", e)))?;
     let result = args::decode(bytes.inner_bytes(), ty, types)?;
-    Ok(result.0)
+    let receipt = events_counts.map(|events_counts| {
+        let call_result = env.last_call_result(contract_address);
+        let caller = call_result.callee_contract_caller();
+        let deploy = container
+            .is_livenet()
+            .then(|| {
+                livenet::find_deploy(&caller, sent_at, |deploy| {
+                    deploy.calls(&contract_address, &sent_call)
+                })
+            })
+            .flatten();
+        // the in-memory Odra VM does not measure gas
+        let gas_used = Some(call_result.callee_contract_gas_used()).filter(|gas| *gas > 0);
+        Receipt {
+            deploy_hash: deploy.as_ref().map(|deploy| deploy.deploy_hash.clone()),
+            caller: caller.to_string(),
+            gas_used: deploy.as_ref().and_then(|deploy| deploy.cost).or(gas_used),
+            attached_value: amount.to_string(),
+            block_height: deploy.as_ref().map(|deploy| deploy.block_height),
+            block_time: deploy.map(|deploy| deploy.block_time),
+            events: events::emitted_since(env, container, &events_counts, types),
+        }
+    });
+    Ok(CallOutput {
+        result: result.0,
+        receipt,
+    })
+}
+
+/// Calls the entry point of a deployed contract and returns the gas used by the call.
+pub fn estimate_gas(
+    env: &HostEnv,
+    container: &DeployedContractsContainer,
+    contract_name: &str,
+    entry_point: &Entrypoint,
+    args: &ArgMatches,
+    types: &CustomTypeSet,
+) -> Result<u64, CallError> {
+    call(env, container, contract_name, entry_point, args, types)?
+        .receipt
+        .ok_or(CallError::ExecutionError(
+            "Gas can be estimated only for a mutable call".to_string(),
+        ))?
+        .gas_used
+        .ok_or(CallError::ExecutionError(
+            "The gas used by the call is not reported".to_string(),
+        ))
 }
@@ -0,0 +1,124 @@
+use thiserror::Error;
+
+use crate::{
+    args::ArgsError,
+    cmd::{
+        deploy::DeployError,
+        scenario::{ArgError, ScenarioError},
+    },
+    config::ConfigError,
+    container::ContractError,
+    entry_point::CallError,
+    livenet::NodeError,
+    types,
+};
+
+/// Error returned by [crate::OdraCli::run].
+///
+/// Each variant maps to a distinct process exit code, see [Error::exit_code].
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid arguments: {0}")]
+    InvalidArgs(String),
+    #[error("Contracts container error: {0}")]
+    Container(String),
+    #[error("Execution reverted: {0}")]
+    Reverted(String),
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Unexpected error: {0}")]
+    Unexpected(String),
+}
+
+impl Error {
+    /// Returns the process exit code for the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidArgs(_) => 2,
+            Error::Container(_) => 3,
+            Error::Reverted(_) => 4,
+            Error::Network(_) => 5,
+            Error::Unexpected(_) => 6,
+        }
+    }
+
+    /// Returns a stable, machine-readable name of the error kind.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::InvalidArgs(_) => "invalid_args",
+            Error::Container(_) => "container",
+            Error::Reverted(_) => "reverted",
+            Error::Network(_) => "network",
+            Error::Unexpected(_) => "unexpected",
+        }
+    }
+}
+
+impl From<ContractError> for Error {
+    fn from(err: ContractError) -> Self {
+        match err {
+            ContractError::InvalidPackageHash(_) => Error::InvalidArgs(err.to_string()),
+            ContractError::Node(NodeError::MissingNodeAddress) => {
+                Error::InvalidArgs(err.to_string())
+            }
+            ContractError::Node(NodeError::Request(_)) => Error::Network(err.to_string()),
+            _ => Error::Container(err.to_string()),
+        }
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::InvalidArgs(err.to_string())
+    }
+}
+
+impl From<ArgsError> for Error {
+    fn from(err: ArgsError) -> Self {
+        Error::InvalidArgs(err.to_string())
+    }
+}
+
+impl From<types::Error> for Error {
+    fn from(err: types::Error) -> Self {
+        Error::InvalidArgs(err.to_string())
+    }
+}
+
+impl From<ArgError> for Error {
+    fn from(err: ArgError) -> Self {
+        Error::InvalidArgs(err.to_string())
+    }
+}
+
+impl From<CallError> for Error {
+    fn from(err: CallError) -> Self {
+        match err {
+            CallError::ExecutionError(_) => Error::Reverted(err.to_string()),
+            CallError::ArgsError(err) => err.into(),
+            CallError::TypesError(err) => err.into(),
+            CallError::ContractNotFound => Error::Container(err.to_string()),
+            CallError::ContractError(err) => err.into(),
+        }
+    }
+}
+
+impl From<DeployError> for Error {
+    fn from(err: DeployError) -> Self {
+        match err {
+            DeployError::OdraError { .. } => Error::Reverted(err.to_string()),
+            DeployError::ContractReadError(err) => err.into(),
+        }
+    }
+}
+
+impl From<ScenarioError> for Error {
+    fn from(err: ScenarioError) -> Self {
+        match err {
+            ScenarioError::OdraError { .. } => Error::Reverted(err.to_string()),
+            ScenarioError::ContractReadError(err) => err.into(),
+            ScenarioError::ArgError(err) => err.into(),
+            ScenarioError::TypesError(err) => err.into(),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use odra::{
+    casper_types::bytesrepr::FromBytes, host::HostEnv, schema::casper_contract_schema::NamedCLType,
+    Address,
+};
+use serde_derive::Serialize;
+use serde_json::Value;
+
+use crate::{args, CustomTypeSet, DeployedContractsContainer};
+
+/// An event emitted by a contract, decoded with the schema of the event.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedEvent {
+    pub name: String,
+    pub data: Value,
+}
+
+/// An event with its position in the event stream of the contract.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexedEvent {
+    pub index: u32,
+    #[serde(flatten)]
+    pub event: DecodedEvent,
+}
+
+/// Events emitted by a single contract.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractEvents {
+    pub contract: String,
+    pub events: Vec<DecodedEvent>,
+}
+
+/// Decodes an event.
+///
+/// The event name is serialized first, prefixed with `event_`, followed by the event fields.
+/// If the event schema is unknown, only the name is decoded.
+pub(crate) fn decode(bytes: &[u8], types: &CustomTypeSet) -> Option<DecodedEvent> {
+    let (name, bytes) = String::from_bytes(bytes).ok()?;
+    let name = name.strip_prefix("event_")?.to_string();
+    let data = args::decode_value(bytes, &NamedCLType::Custom(name.clone()), types)
+        .map(|(data, _)| data)
+        .unwrap_or(Value::Null);
+    Some(DecodedEvent { name, data })
+}
+
+/// Counts the events emitted so far by each contract in the container.
+pub(crate) fn counts(env: &HostEnv, container: &DeployedContractsContainer) -> Vec<u32> {
+    addresses(container)
+        .iter()
+        .map(|(_, address)| env.events_count(address))
+        .collect()
+}
+
+/// Decodes the events emitted by the contracts in the container since the counts were taken.
+pub(crate) fn emitted_since(
+    env: &HostEnv,
+    container: &DeployedContractsContainer,
+    counts: &[u32],
+    types: &CustomTypeSet,
+) -> Vec<ContractEvents> {
+    addresses(container)
+        .into_iter()
+        .zip(counts)
+        .filter_map(|((name, address), count)| {
+            let events = (*count..env.events_count(&address))
+                .filter_map(|index| env.get_event_bytes(&address, index).ok())
+                .filter_map(|bytes| decode(&bytes, types))
+                .collect::<Vec<_>>();
+            (!events.is_empty()).then(|| ContractEvents {
+                contract: name.to_string(),
+                events,
+            })
+        })
+        .collect()
+}
+
+fn addresses(container: &DeployedContractsContainer) -> Vec<(&str, Address)> {
+    container
+        .contracts()
+        .iter()
+        .filter_map(|contract| contract.address().map(|address| (contract.name(), address)))
+        .collect()
+}
+
+#[cfg(test)]
+mod t {
+    use odra::casper_types::bytesrepr::ToBytes;
+    use serde_json::json;
+
+    use crate::test_utils::{self, NameRegistered};
+
+    #[test]
+    fn test_decode() {
+        let owner = "hash-f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a";
+        let event = NameRegistered {
+            label: "kpob".to_string(),
+            owner: owner.parse().unwrap(),
+        };
+        let bytes = event.to_bytes().unwrap();
+
+        let decoded = super::decode(&bytes, &test_utils::custom_types()).unwrap();
+        pretty_assertions::assert_eq!(decoded.name, "NameRegistered");
+        pretty_assertions::assert_eq!(
+            decoded.data,
+            json!({
+                "label": "kpob",
+                "owner": "Key::Hash(f01cec215ddfd4c4a19d58f9c917023391a1da871e047dc47a83ae55f6cfc20a)"
+            })
+        );
+
+        let decoded = super::decode(&bytes, &Default::default()).unwrap();
+        pretty_assertions::assert_eq!(decoded.name, "NameRegistered");
+        pretty_assertions::assert_eq!(decoded.data, json!(null));
+    }
+}
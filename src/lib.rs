@@ -62,34 +62,59 @@
 //!         .scenario(MyScenario)
 //!         // .scenario(AnotherScenario)
 //!         .build();
-//!     //cli.run();
+//!     // if let Err(err) = cli.run() {
+//!     //     std::process::exit(err.exit_code());
+//!     // }
 //! }
 //! ```
 
 #![feature(box_patterns, error_generic_member_access)]
-use std::collections::BTreeSet;
+#![allow(clippy::needless_doctest_main)]
+use std::{collections::BTreeSet, ffi::OsString, path::PathBuf};
 
-use clap::{command, Arg, Command};
-use cmd::{OdraCliCommand, OdraCommand};
+use backend::Backend;
+use clap::{command, Arg, ArgMatches, Command};
+use cmd::{
+    config::CONFIG_SUBCOMMAND, contracts::CONTRACTS_INFO_SUBCOMMAND, events::EVENTS_SUBCOMMAND,
+    import::ImportCmd, OdraCliCommand, OdraCommand,
+};
+use config::Config;
+use container::ContainerLocation;
 use deploy::DeployScript;
 use odra::{
     contract_def::HasIdent,
-    host::{EntryPointsCallerProvider, HostEnv},
-    schema::{casper_contract_schema::CustomType, SchemaCustomTypes, SchemaEntrypoints},
+    host::HostEnv,
+    schema::{
+        casper_contract_schema::CustomType, SchemaCustomTypes, SchemaEntrypoints, SchemaEvents,
+    },
     OdraContract,
 };
+use output::{CommandOutput, OutputFormat};
 
 mod args;
+mod backend;
 mod cmd;
+mod completions;
+mod config;
 mod container;
+mod dry_run;
 mod entry_point;
+mod error;
+mod events;
+mod livenet;
+mod output;
+mod prompt;
+mod shell;
 #[cfg(test)]
 mod test_utils;
 mod types;
 
 pub use args::CommandArg;
+use completions::COMPLETIONS_SUBCOMMAND;
 pub use container::DeployedContractsContainer;
+pub use error::Error;
 use scenario::{Scenario, ScenarioMetadata};
+use shell::SHELL_SUBCOMMAND;
 
 const CONTRACTS_SUBCOMMAND: &str = "contract";
 const SCENARIOS_SUBCOMMAND: &str = "scenario";
@@ -123,7 +148,13 @@ pub struct OdraCli {
     contracts_cmd: Command,
     commands: Vec<OdraCliCommand>,
     custom_types: CustomTypeSet,
-    host_env: HostEnv,
+    contracts_file: Option<PathBuf>,
+}
+
+impl Default for OdraCli {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl OdraCli {
@@ -139,13 +170,21 @@ impl OdraCli {
             .arg_required_else_help(true);
         let main_cmd = Command::new("Odra CLI")
             .subcommand_required(true)
-            .arg_required_else_help(true);
+            .arg_required_else_help(true)
+            .arg(args::network_arg())
+            .arg(args::contracts_file_arg())
+            .arg(args::backend_arg())
+            .arg(args::node_address_arg())
+            .arg(args::chain_name_arg())
+            .arg(args::secret_key_path_arg())
+            .arg(args::output_arg())
+            .arg(args::interactive_arg());
 
         Self {
             main_cmd,
             commands: vec![],
             custom_types: CustomTypeSet::new(),
-            host_env: odra_casper_livenet_env::env(),
+            contracts_file: None,
             contracts_cmd,
             scenarios_cmd,
         }
@@ -157,29 +196,61 @@ impl OdraCli {
         self
     }
 
+    /// Sets the file the deployed contracts are stored in.
+    ///
+    /// By default, the file is resolved against the project root, which is not available if
+    /// the binary is run outside of the cargo project. The file can be overridden with
+    /// the `--contracts-file` flag or the `ODRA_CLI_CONTRACTS_FILE` environment variable.
+    /// A file with the `.json` extension is stored as JSON.
+    pub fn contracts_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.contracts_file = Some(path.into());
+        self
+    }
+
     /// Adds a contract to the CLI.
     ///
     /// Generates a subcommand for the contract with all of its entry points except the `init` entry point.
     /// To call the constructor of the contract, implement and register the [DeployScript].
-    pub fn contract<T: SchemaEntrypoints + SchemaCustomTypes + OdraContract>(mut self) -> Self {
-        let contract_name = T::HostRef::ident();
-        if let Ok(container) = DeployedContractsContainer::load() {
-            let caller = T::HostRef::entry_points_caller(&self.host_env);
-            let address = container
-                .address(&contract_name)
-                .expect("Contract not found");
-            self.host_env
-                .register_contract(address, contract_name.clone(), caller);
-        }
+    ///
+    /// The types of the events emitted by the contract are registered as well,
+    /// so the events can be decoded.
+    pub fn contract<T: SchemaEntrypoints + SchemaCustomTypes + SchemaEvents + OdraContract>(
+        self,
+    ) -> Self {
+        self.contract_named::<T>(&T::HostRef::ident())
+    }
+
+    /// Adds a contract to the CLI under the given alias.
+    ///
+    /// Works like [OdraCli::contract], but the subcommand is named after the alias and calls
+    /// the contract stored under the alias in the [DeployedContractsContainer]. Use it to interact
+    /// with multiple instances of the same contract, eg. `contract usdt transfer`.
+    pub fn contract_named<
+        T: SchemaEntrypoints + SchemaCustomTypes + SchemaEvents + OdraContract,
+    >(
+        mut self,
+        alias: &str,
+    ) -> Self {
+        let contract_name = alias.to_string();
+        let ident = T::HostRef::ident();
+        let about = match contract_name == ident {
+            true => format!(
+                "Commands for interacting with the {} contract",
+                &contract_name
+            ),
+            false => format!(
+                "Commands for interacting with the {} contract ({})",
+                &contract_name, ident
+            ),
+        };
+        self.custom_types
+            .extend(T::schema_types().into_iter().flatten());
         self.custom_types
-            .extend(T::schema_types().into_iter().filter_map(|ty| ty));
+            .extend(<T as SchemaEvents>::custom_types().into_iter().flatten());
 
         // build entry points commands
         let mut contract_cmd = Command::new(&contract_name)
-            .about(format!(
-                "Commands for interacting with the {} contract",
-                &contract_name
-            ))
+            .about(about)
             .subcommand_required(true)
             .arg_required_else_help(true);
         for entry_point in T::schema_entrypoints() {
@@ -192,8 +263,32 @@ impl OdraCli {
                 ep_cmd = ep_cmd.arg(arg);
             }
             ep_cmd = ep_cmd.arg(args::attached_value_arg());
+            // the gas is set only for a deploy, so it is not accepted by a state query
+            if entry_point.is_mutable {
+                ep_cmd = ep_cmd
+                    .arg(args::gas_arg())
+                    .arg(args::simulate_arg())
+                    .arg(args::receipt_arg());
+                #[cfg(feature = "casper-vm")]
+                {
+                    ep_cmd = ep_cmd.arg(args::estimate_gas_arg());
+                }
+            }
             contract_cmd = contract_cmd.subcommand(ep_cmd);
         }
+        // an entry point takes precedence over the events command
+        if contract_cmd.find_subcommand(EVENTS_SUBCOMMAND).is_none() {
+            contract_cmd = contract_cmd.subcommand(
+                Command::new(EVENTS_SUBCOMMAND)
+                    .about(format!(
+                        "Prints the events emitted by the {} contract",
+                        &contract_name
+                    ))
+                    .arg(args::events_from_arg())
+                    .arg(args::events_limit_arg())
+                    .arg(args::events_follow_arg()),
+            );
+        }
         self.contracts_cmd = self.contracts_cmd.subcommand(contract_cmd);
 
         // store a command
@@ -207,9 +302,14 @@ impl OdraCli {
     /// There is only one deploy script allowed in the CLI.
     pub fn deploy(mut self, script: impl DeployScript + 'static) -> Self {
         // register a subcommand for the deploy script
-        self.main_cmd = self
-            .main_cmd
-            .subcommand(command!(DEPLOY_SUBCOMMAND).about("Runs the deploy script"));
+        self.main_cmd = self.main_cmd.subcommand(
+            command!(DEPLOY_SUBCOMMAND)
+                .about("Runs the deploy script")
+                .arg(args::resume_arg())
+                .arg(args::payment_arg())
+                .args_conflicts_with_subcommands(true)
+                .subcommands(cmd::deploy::history_subcommands()),
+        );
         // store a command
         self.commands.push(OdraCliCommand::new_deploy(script));
         self
@@ -241,69 +341,413 @@ impl OdraCli {
 
     /// Builds the CLI.
     pub fn build(mut self) -> Self {
+        let contract_names = self
+            .commands
+            .iter()
+            .filter(|cmd| matches!(cmd, OdraCliCommand::Contract(_)))
+            .map(|cmd| cmd.name().to_string())
+            .collect::<Vec<_>>();
+        if !contract_names.is_empty() {
+            self.contracts_cmd = self
+                .contracts_cmd
+                .subcommand(ImportCmd::subcommand(contract_names));
+            self.commands.push(OdraCliCommand::new_import());
+        }
         self.main_cmd = self.main_cmd.subcommand(self.contracts_cmd.clone());
         self.main_cmd = self.main_cmd.subcommand(self.scenarios_cmd.clone());
+        self.main_cmd = self.main_cmd.subcommand(cmd::contracts::command());
+        self.main_cmd = self.main_cmd.subcommand(cmd::config::command());
+        self.main_cmd = self.main_cmd.subcommand(shell::command());
+        self.main_cmd = self.main_cmd.subcommand(completions::command());
         self
     }
 
     /// Runs the CLI and parses the input.
-    pub fn run(self) {
-        let matches = self.main_cmd.get_matches();
-        let (cmd, args) = matches
+    ///
+    /// The result is printed in the selected output format. If the command fails, the error
+    /// is printed and returned, so the caller can exit the process with [Error::exit_code].
+    pub fn run(self) -> Result<(), Error> {
+        config::load_env_files();
+        let input = std::env::args_os().collect::<Vec<_>>();
+        // the input is parsed leniently to find the config file of the selected network profile
+        let lenient = self
+            .main_cmd
+            .clone()
+            .ignore_errors(true)
+            .try_get_matches_from(&input)
+            .ok();
+        let mut applied = Vec::new();
+        if let Some(lenient) = &lenient {
+            // the config provides the defaults of the flags, so it is applied before parsing
+            match Config::load(&self.location(lenient)) {
+                Ok(config) => applied = config.apply(),
+                Err(err) => {
+                    let err = Error::from(err);
+                    args::output(lenient).print_error(&err);
+                    return Err(err);
+                }
+            }
+        }
+        let matches = match self.parse_input(input, lenient.as_ref()) {
+            Ok(matches) => matches,
+            // help and version are reported as errors by clap, but are not failures
+            Err(err) if !err.use_stderr() => {
+                let _ = err.print();
+                return Ok(());
+            }
+            Err(clap_err) => {
+                let err = Error::InvalidArgs(clap_err.to_string());
+                match lenient.as_ref().map(args::output) {
+                    Some(OutputFormat::Json) => OutputFormat::Json.print_error(&err),
+                    _ => {
+                        let _ = clap_err.print();
+                    }
+                }
+                return Err(err);
+            }
+        };
+        config::export(&matches);
+        let output = args::output(&matches);
+        output.redirect_logs();
+        let backend = match select_backend(&matches) {
+            Ok(backend) => backend,
+            Err(err) => {
+                output.print_error(&err);
+                return Err(err);
+            }
+        };
+        let location = self.location(&matches);
+        match matches.subcommand() {
+            Some((SHELL_SUBCOMMAND, _)) => return self.run_shell(&matches, backend, &location),
+            Some((COMPLETIONS_SUBCOMMAND, sub_matches)) => {
+                completions::run(&self.main_cmd, sub_matches);
+                return Ok(());
+            }
+            _ => {}
+        }
+        let result = match matches.subcommand() {
+            Some((DEPLOY_SUBCOMMAND, sub_matches)) if sub_matches.subcommand().is_some() => {
+                cmd::deploy::run_history(sub_matches, &location)
+            }
+            Some((CONTRACTS_INFO_SUBCOMMAND, sub_matches)) => {
+                cmd::contracts::run(sub_matches, &self.commands, &location)
+            }
+            Some((CONFIG_SUBCOMMAND, sub_matches)) => {
+                cmd::config::run(sub_matches, &location, &applied)
+            }
+            _ => self.run_command(&matches, backend, &location),
+        };
+
+        match result {
+            Ok(result) => {
+                output.print_success(&result);
+                Ok(())
+            }
+            Err(err) => {
+                output.print_error(&err);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the location of the contracts container selected by the parsed input.
+    fn location(&self, matches: &ArgMatches) -> ContainerLocation {
+        ContainerLocation::new(
+            args::contracts_file(matches).or(self.contracts_file.clone()),
+            args::network(matches),
+        )
+    }
+
+    /// Parses the command line input.
+    ///
+    /// In the interactive mode, the required args missing in the command selected by
+    /// the leniently parsed input are prompted for and appended to the input.
+    fn parse_input(
+        &self,
+        mut input: Vec<OsString>,
+        lenient: Option<&ArgMatches>,
+    ) -> Result<ArgMatches, clap::Error> {
+        if let Some((cmd, args)) = lenient
+            .filter(|matches| args::interactive(matches))
+            .and_then(|matches| self.find_command(matches))
+        {
+            let missing = cmd.missing_args(args, &self.custom_types);
+            input.extend(prompt::prompt(&missing, &self.custom_types));
+        }
+        self.main_cmd.clone().try_get_matches_from(input)
+    }
+
+    /// Runs the command selected by the parsed input on the given backend.
+    fn run_command(
+        &self,
+        matches: &ArgMatches,
+        backend: Backend,
+        location: &ContainerLocation,
+    ) -> Result<CommandOutput, Error> {
+        self.find_command(matches)
+            .ok_or(Error::InvalidArgs("Subcommand not found".to_string()))
+            .and_then(|(cmd, args)| {
+                // a contract is imported to the container only, so no environment is built
+                if let OdraCliCommand::Import(import) = cmd {
+                    let mut container = match backend.is_persistent() {
+                        // a contract can be imported before anything is deployed
+                        true => DeployedContractsContainer::resume(location)?,
+                        false => DeployedContractsContainer::in_memory(),
+                    };
+                    return import.import(&mut container, args);
+                }
+                // the Casper VM fails without naming the wasm file it can not find
+                if let OdraCliCommand::Contract(contract) = cmd {
+                    if args::nested_flag(args, args::ESTIMATE_GAS_ARG) {
+                        contract.check_wasm()?;
+                    }
+                }
+                backend.guard(|| {
+                    let env = match cmd.sends_deploy(args) {
+                        true => backend.env()?,
+                        false => backend.dry_run_env()?,
+                    };
+                    let mut container = prepare_container(
+                        &self.commands,
+                        cmd,
+                        backend,
+                        &env,
+                        matches,
+                        location,
+                        &self.custom_types,
+                    )?;
+                    cmd.run(&env, &mut container, args, &self.custom_types)
+                })
+            })
+    }
+
+    /// Runs the shell, the host environment and the container are prepared once
+    /// and shared by all the commands.
+    ///
+    /// The backend and the network profile are selected when the shell is started.
+    fn run_shell(
+        &self,
+        matches: &ArgMatches,
+        backend: Backend,
+        location: &ContainerLocation,
+    ) -> Result<(), Error> {
+        let prepared = backend.guard(|| {
+            let env = backend.env()?;
+            let container = load_container(
+                &self.commands,
+                backend,
+                &env,
+                matches,
+                location,
+                &self.custom_types,
+            )?;
+            Ok((env, container))
+        });
+        let (env, mut container) = match prepared {
+            Ok(prepared) => prepared,
+            Err(err) => {
+                args::output(matches).print_error(&err);
+                return Err(err);
+            }
+        };
+
+        let available = [CONTRACTS_SUBCOMMAND, SCENARIOS_SUBCOMMAND];
+        shell::run(&self.main_cmd, &available, |matches| {
+            let result = match matches.subcommand() {
+                // the gas is estimated on a separate backend
+                _ if args::nested_flag(&matches, args::ESTIMATE_GAS_ARG) => Err(
+                    Error::InvalidArgs("Estimating gas is not available in the shell".to_string()),
+                ),
+                // the in-memory state is shared by the commands, so a simulated call would change it
+                _ if args::nested_flag(&matches, args::SIMULATE_ARG)
+                    && !backend.is_persistent() =>
+                {
+                    Err(Error::InvalidArgs(
+                        "Simulating a call in the shell is available only on the livenet backend"
+                            .to_string(),
+                    ))
+                }
+                // the loop never returns to the prompt
+                _ if args::nested_flag(&matches, args::EVENTS_FOLLOW_ARG) => {
+                    Err(Error::InvalidArgs(
+                        "Following the events is not available in the shell".to_string(),
+                    ))
+                }
+                Some((name, _)) if available.contains(&name) => self
+                    .find_command(&matches)
+                    .ok_or(Error::InvalidArgs("Subcommand not found".to_string()))
+                    .and_then(|(cmd, args)| {
+                        backend.guard(|| match cmd.sends_deploy(args) {
+                            true => cmd.run(&env, &mut container, args, &self.custom_types),
+                            // a dry run gets its own environment, so its changes are discarded
+                            false => {
+                                let env = backend.dry_run_env()?;
+                                let mut container = load_container(
+                                    &self.commands,
+                                    backend,
+                                    &env,
+                                    &matches,
+                                    location,
+                                    &self.custom_types,
+                                )?;
+                                cmd.run(&env, &mut container, args, &self.custom_types)
+                            }
+                        })
+                    }),
+                _ => Err(Error::InvalidArgs(
+                    "Only the contract and scenario commands are available in the shell"
+                        .to_string(),
+                )),
+            };
+            let output = args::output(&matches);
+            match result {
+                Ok(result) => output.print_success(&result),
+                Err(err) => output.print_error(&err),
+            }
+        });
+        Ok(())
+    }
+
+    /// Finds the command selected by the parsed input.
+    fn find_command<'a>(
+        &'a self,
+        matches: &'a ArgMatches,
+    ) -> Option<(&'a OdraCliCommand, &'a ArgMatches)> {
+        matches
             .subcommand()
-            .map(|(subcommand, sub_matches)| match subcommand {
+            .and_then(|(subcommand, sub_matches)| match subcommand {
                 DEPLOY_SUBCOMMAND => {
                     find_deploy(&self.commands).map(|deploy| (deploy, sub_matches))
                 }
                 CONTRACTS_SUBCOMMAND => {
                     sub_matches
                         .subcommand()
-                        .map(|(contract_name, entrypoint_matches)| {
-                            (
-                                find_contract(&self.commands, contract_name),
-                                entrypoint_matches,
-                            )
+                        .and_then(|(contract_name, entrypoint_matches)| {
+                            find_contract(&self.commands, contract_name)
+                                .or_else(|| find_import(&self.commands, contract_name))
+                                .map(|contract| (contract, entrypoint_matches))
                         })
                 }
                 SCENARIOS_SUBCOMMAND => {
-                    sub_matches.subcommand().map(|(subcommand, sub_matches)| {
-                        (find_scenario(&self.commands, subcommand), sub_matches)
-                    })
+                    sub_matches
+                        .subcommand()
+                        .and_then(|(subcommand, sub_matches)| {
+                            find_scenario(&self.commands, subcommand)
+                                .map(|scenario| (scenario, sub_matches))
+                        })
                 }
-                _ => unreachable!(),
+                _ => None,
             })
-            .flatten()
-            .expect("Subcommand not found");
+    }
+}
 
-        match cmd.run(&self.host_env, args, &self.custom_types) {
-            Ok(_) => prettycli::info("Command executed successfully"),
-            Err(err) => prettycli::error(&format!("{:?}", err)),
+/// Selects the backend the command is run on.
+///
+/// The gas is measured only by the Casper VM, so it can not be estimated on a backend
+/// selected by the user.
+fn select_backend(matches: &ArgMatches) -> Result<Backend, Error> {
+    #[cfg(feature = "casper-vm")]
+    if args::nested_flag(matches, args::ESTIMATE_GAS_ARG) {
+        if args::is_backend_selected(matches) {
+            return Err(Error::InvalidArgs(
+                "The gas is estimated on the Casper VM, --backend can't be used with --estimate-gas"
+                    .to_string(),
+            ));
         }
+        return Ok(Backend::CasperVm);
     }
+    Ok(args::backend(matches))
 }
 
-fn find_scenario<'a>(commands: &'a [OdraCliCommand], name: &str) -> &'a OdraCliCommand {
-    commands
-        .iter()
-        .find(|cmd| match cmd {
-            OdraCliCommand::Scenario(scenario) => scenario.name() == name,
-            _ => false,
-        })
-        .unwrap()
+/// Prepares the container the command is run against.
+fn prepare_container(
+    commands: &[OdraCliCommand],
+    cmd: &OdraCliCommand,
+    backend: Backend,
+    env: &HostEnv,
+    matches: &ArgMatches,
+    location: &ContainerLocation,
+    types: &CustomTypeSet,
+) -> Result<DeployedContractsContainer, Error> {
+    let is_deploy = matches!(cmd, OdraCliCommand::Deploy(_));
+    if !backend.is_persistent() {
+        if is_deploy {
+            return Ok(DeployedContractsContainer::in_memory());
+        }
+    } else if is_deploy {
+        return match args::nested_flag(matches, args::RESUME_ARG) {
+            true => Ok(DeployedContractsContainer::resume(location)?),
+            false => Ok(DeployedContractsContainer::new(location)?),
+        };
+    }
+    load_container(commands, backend, env, matches, location, types)
 }
 
-fn find_deploy<'a>(commands: &'a [OdraCliCommand]) -> Option<&'a OdraCliCommand> {
+/// Loads the container the contracts are called against.
+///
+/// The in-memory backend starts with an empty state, so the deploy script is run
+/// to rehearse the deployment. The gas can not be estimated without the script,
+/// as there is nothing to call.
+fn load_container(
+    commands: &[OdraCliCommand],
+    backend: Backend,
+    env: &HostEnv,
+    matches: &ArgMatches,
+    location: &ContainerLocation,
+    types: &CustomTypeSet,
+) -> Result<DeployedContractsContainer, Error> {
+    if !backend.is_persistent() {
+        let mut container = DeployedContractsContainer::in_memory();
+        match find_deploy(commands) {
+            Some(deploy) => {
+                deploy.run(env, &mut container, matches, types)?;
+            }
+            None if args::nested_flag(matches, args::ESTIMATE_GAS_ARG) => {
+                return Err(Error::InvalidArgs(
+                    "Estimating gas requires a deploy script".to_string(),
+                ));
+            }
+            None => {}
+        }
+        return Ok(container);
+    }
+
+    // register deployed contracts of the selected network profile
+    let container = DeployedContractsContainer::load(location)?;
+    commands.iter().for_each(|cmd| {
+        if let OdraCliCommand::Contract(contract) = cmd {
+            contract.register(env, &container);
+        }
+    });
+    Ok(container)
+}
+
+fn find_scenario<'a>(commands: &'a [OdraCliCommand], name: &str) -> Option<&'a OdraCliCommand> {
+    commands.iter().find(|cmd| match cmd {
+        OdraCliCommand::Scenario(scenario) => scenario.name() == name,
+        _ => false,
+    })
+}
+
+fn find_deploy(commands: &[OdraCliCommand]) -> Option<&OdraCliCommand> {
     commands
         .iter()
         .find(|cmd| matches!(cmd, OdraCliCommand::Deploy(_)))
 }
 
-fn find_contract<'a>(commands: &'a [OdraCliCommand], contract_name: &str) -> &'a OdraCliCommand {
-    commands
-        .iter()
-        .find(|cmd| match cmd {
-            OdraCliCommand::Contract(contract) => contract.name() == contract_name,
-            _ => false,
-        })
-        .unwrap()
+fn find_import<'a>(commands: &'a [OdraCliCommand], name: &str) -> Option<&'a OdraCliCommand> {
+    commands.iter().find(|cmd| match cmd {
+        OdraCliCommand::Import(import) => import.name() == name,
+        _ => false,
+    })
+}
+
+fn find_contract<'a>(
+    commands: &'a [OdraCliCommand],
+    contract_name: &str,
+) -> Option<&'a OdraCliCommand> {
+    commands.iter().find(|cmd| match cmd {
+        OdraCliCommand::Contract(contract) => contract.name() == contract_name,
+        _ => false,
+    })
 }
@@ -0,0 +1,279 @@
+//! Queries of the Casper node the livenet backend is connected to.
+//!
+//! The livenet host environment does not expose the on-chain details of the deployed contracts,
+//! so the node is asked directly, configured by the same environment variables.
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use odra::{
+    casper_types::{
+        bytesrepr::{Bytes, ToBytes},
+        runtime_args, AsymmetricType, Key, PublicKey, RuntimeArgs,
+    },
+    Address, CallDef,
+};
+use odra_core::consts;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+pub(crate) const ENV_NODE_ADDRESS: &str = "ODRA_CASPER_LIVENET_NODE_ADDRESS";
+pub(crate) const ENV_CHAIN_NAME: &str = "ODRA_CASPER_LIVENET_CHAIN_NAME";
+pub(crate) const ENV_SECRET_KEY_PATH: &str = "ODRA_CASPER_LIVENET_SECRET_KEY_PATH";
+/// The prefix of the variables holding the secret keys of the additional accounts.
+pub(crate) const ENV_ACCOUNT_PREFIX: &str = "ODRA_CASPER_LIVENET_KEY_";
+pub(crate) const ENV_LIVENET_ENV_FILE: &str = "ODRA_CASPER_LIVENET_ENV";
+pub(crate) const ENV_CSPR_CLOUD_AUTH_TOKEN: &str = "CSPR_CLOUD_AUTH_TOKEN";
+/// The number of the latest blocks searched for a deploy.
+const SEARCHED_BLOCKS: usize = 10;
+
+/// A failure to get a response from the node.
+#[derive(Error, Debug)]
+pub enum NodeError {
+    #[error("{ENV_NODE_ADDRESS} must be set")]
+    MissingNodeAddress,
+    #[error("Couldn't query the node: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// The latest version of a contract package.
+pub(crate) struct ContractVersion {
+    pub contract_hash: String,
+    pub version: u32,
+}
+
+/// A deploy executed by the node.
+pub(crate) struct ExecutedDeploy {
+    pub deploy_hash: String,
+    pub block_height: u64,
+    pub block_time: String,
+    pub cost: Option<u64>,
+    /// The contract package written by the deploy.
+    pub package_hash: Option<String>,
+    /// The session code of the deploy.
+    session: Value,
+}
+
+impl ExecutedDeploy {
+    /// Returns `true` if the deploy calls the entry point of the contract with exactly
+    /// the given args, directly or through the proxy caller.
+    pub fn calls(&self, contract: &Address, call_def: &CallDef) -> bool {
+        let Some(package_hash) = contract.as_contract_package_hash() else {
+            return false;
+        };
+        if let Some(session) = self.session.get("StoredVersionedContractByHash") {
+            return session["hash"] == hex::encode(package_hash.value())
+                && session["entry_point"] == call_def.entry_point()
+                && session_args(session) == expected_args(call_def.args());
+        }
+        // the proxy caller gets the serialized args of the call
+        let Some(session) = self.session.get("ModuleBytes") else {
+            return false;
+        };
+        let Ok(call_args) = call_def.args().to_bytes() else {
+            return false;
+        };
+        let proxy_args = runtime_args! {
+            consts::CONTRACT_PACKAGE_HASH_ARG => *package_hash,
+            consts::ENTRY_POINT_ARG => call_def.entry_point(),
+            consts::ARGS_ARG => Bytes::from(call_args),
+        };
+        let session_args = session_args(session);
+        expected_args(&proxy_args)
+            .iter()
+            .all(|(name, bytes)| session_args.get(name) == Some(bytes))
+    }
+}
+
+/// Returns the hex encoded bytes of the args of a deploy session, by name.
+fn session_args(session: &Value) -> BTreeMap<String, String> {
+    session["args"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| {
+            Some((
+                arg[0].as_str()?.to_string(),
+                arg[1]["bytes"].as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+fn expected_args(args: &RuntimeArgs) -> BTreeMap<String, String> {
+    args.named_args()
+        .map(|arg| {
+            (
+                arg.name().to_string(),
+                hex::encode(arg.cl_value().inner_bytes()),
+            )
+        })
+        .collect()
+}
+
+/// Returns the name of the chain the livenet backend is connected to.
+pub(crate) fn chain_name() -> Option<String> {
+    std::env::var(ENV_CHAIN_NAME).ok()
+}
+
+/// Queries the node for the latest version of the contract package at the given address.
+///
+/// Returns `None` if the package is not found.
+pub(crate) fn latest_contract_version(
+    address: &Address,
+) -> Result<Option<ContractVersion>, NodeError> {
+    let Some(package_hash) = address.as_contract_package_hash() else {
+        return Ok(None);
+    };
+    let params = json!({
+        "key": Key::Hash(package_hash.value()).to_formatted_string(),
+        "path": []
+    });
+    // the node responds with an error if there is nothing stored under the key
+    let Some(result) = rpc("query_global_state", params)? else {
+        return Ok(None);
+    };
+    let Some(versions) = result["stored_value"]["ContractPackage"]["versions"].as_array() else {
+        return Ok(None);
+    };
+    Ok(versions
+        .iter()
+        .filter_map(|version| {
+            Some(ContractVersion {
+                contract_hash: version["contract_hash"].as_str()?.to_string(),
+                version: version["contract_version"].as_u64()? as u32,
+            })
+        })
+        .max_by_key(|version| version.version))
+}
+
+/// Finds the latest deploy of the given account sent after the given time and accepted
+/// by the filter.
+///
+/// The livenet host environment does not return the deploys it sends, so the latest blocks
+/// are searched for it. The filter should match the exact deploy, eg. [ExecutedDeploy::calls],
+/// as the account may send other deploys meanwhile. Returns `None` if the node can not
+/// be reached or the deploy is not found.
+pub(crate) fn find_deploy(
+    account: &Address,
+    since: DateTime<Utc>,
+    filter: impl Fn(&ExecutedDeploy) -> bool,
+) -> Option<ExecutedDeploy> {
+    let account = account.as_account_hash()?;
+    let mut block = rpc("chain_get_block", json!({})).ok()??["block"].take();
+    for _ in 0..SEARCHED_BLOCKS {
+        let block_time = block["header"]["timestamp"].as_str()?.to_string();
+        if timestamp(&block_time)? < since {
+            return None;
+        }
+        let deploy_hashes = block["body"]["deploy_hashes"].as_array()?;
+        for deploy_hash in deploy_hashes.iter().rev().filter_map(Value::as_str) {
+            let result = rpc("info_get_deploy", json!({ "deploy_hash": deploy_hash })).ok()??;
+            let header = &result["deploy"]["header"];
+            let is_sent = header["account"]
+                .as_str()
+                .and_then(|key| PublicKey::from_hex(key).ok())
+                .is_some_and(|key| key.to_account_hash() == *account)
+                && header["timestamp"]
+                    .as_str()
+                    .and_then(timestamp)
+                    .is_some_and(|time| time >= since);
+            if !is_sent {
+                continue;
+            }
+            let execution = &result["execution_results"][0]["result"];
+            let execution = execution.get("Success").or(execution.get("Failure"))?;
+            let written = |transform: &str| {
+                execution["effect"]["transforms"]
+                    .as_array()?
+                    .iter()
+                    .find(|entry| entry["transform"] == transform)
+                    .and_then(|entry| entry["key"].as_str())
+                    .map(str::to_string)
+            };
+            let deploy = ExecutedDeploy {
+                deploy_hash: deploy_hash.to_string(),
+                block_height: block["header"]["height"].as_u64()?,
+                block_time: block_time.clone(),
+                cost: execution["cost"]
+                    .as_str()
+                    .and_then(|cost| cost.parse().ok()),
+                package_hash: written("WriteContractPackage"),
+                session: result["deploy"]["session"].clone(),
+            };
+            if filter(&deploy) {
+                return Some(deploy);
+            }
+        }
+        let parent_hash = block["header"]["parent_hash"].as_str()?.to_string();
+        let params = json!({ "block_identifier": { "Hash": parent_hash } });
+        block = rpc("chain_get_block", params).ok()??["block"].take();
+    }
+    None
+}
+
+fn timestamp(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Sends a JSON-RPC request to the node, returns `None` if the node responds with an error.
+fn rpc(method: &str, params: Value) -> Result<Option<Value>, NodeError> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params,
+        "id": 1,
+    });
+    Ok(post(request)?.get_mut("result").map(Value::take))
+}
+
+fn post(request: Value) -> Result<Value, NodeError> {
+    let node_address =
+        std::env::var(ENV_NODE_ADDRESS).map_err(|_| NodeError::MissingNodeAddress)?;
+    let mut client = reqwest::blocking::Client::new().post(format!("{}/rpc", node_address));
+    if let Ok(token) = std::env::var(ENV_CSPR_CLOUD_AUTH_TOKEN) {
+        client = client.header("Authorization", token);
+    }
+    Ok(client.json(&request).send()?.json()?)
+}
+
+#[cfg(test)]
+mod t {
+    use odra::{
+        casper_types::{bytesrepr::ToBytes, runtime_args, RuntimeArgs},
+        Address, CallDef,
+    };
+    use serde_json::json;
+    use std::str::FromStr;
+
+    use super::ExecutedDeploy;
+
+    #[test]
+    fn test_calls() {
+        let package_hash = "0000000000000000000000000000000000000000000000000000000000000001";
+        let contract = Address::from_str(&format!("hash-{}", package_hash)).unwrap();
+        let label = hex::encode("alice".to_string().to_bytes().unwrap());
+        let deploy = ExecutedDeploy {
+            deploy_hash: "01".to_string(),
+            block_height: 1,
+            block_time: "2024-05-01T10:00:00Z".to_string(),
+            cost: None,
+            package_hash: None,
+            session: json!({
+                "StoredVersionedContractByHash": {
+                    "hash": package_hash,
+                    "version": null,
+                    "entry_point": "register",
+                    "args": [["label", { "cl_type": "String", "bytes": label, "parsed": "alice" }]]
+                }
+            }),
+        };
+
+        let call = |label: &str| CallDef::new("register", true, runtime_args! { "label" => label });
+        assert!(deploy.calls(&contract, &call("alice")));
+        assert!(!deploy.calls(&contract, &call("bob")));
+        let other = Address::from_str(&format!("hash-{:0>64}", 2)).unwrap();
+        assert!(!deploy.calls(&other, &call("alice")));
+    }
+}
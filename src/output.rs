@@ -0,0 +1,378 @@
+use std::{fs::File, io::Write, str::FromStr, sync::OnceLock};
+
+use clap::ValueEnum;
+use odra::schema::casper_contract_schema::{NamedCLType, Type};
+use serde_derive::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    args,
+    cmd::{
+        config::{ConfigEntry, ConfigSource},
+        contracts::EntryPointInfo,
+    },
+    config,
+    container::DeployedContract,
+    entry_point::Receipt,
+    events::{ContractEvents, DecodedEvent, IndexedEvent},
+    DeployedContractsContainer, Error,
+};
+
+/// Format in which the results of the commands are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human readable messages.
+    Text,
+    /// A single JSON document printed to the standard output, the logs go to the standard error.
+    Json,
+}
+
+/// The original standard output, once the logs are redirected to the standard error.
+static DOCUMENTS: OnceLock<File> = OnceLock::new();
+
+/// The result of a successfully executed command.
+#[derive(Debug, Serialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub(crate) enum CommandOutput {
+    Deploy {
+        contracts: Vec<DeployedContract>,
+    },
+    Call {
+        contract: String,
+        entry_point: String,
+        result: Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        receipt: Option<Receipt>,
+    },
+    GasEstimate {
+        contract: String,
+        entry_point: String,
+        gas: u64,
+    },
+    Scenario {
+        name: String,
+        events: Vec<ContractEvents>,
+    },
+    Events {
+        contract: String,
+        events: Vec<IndexedEvent>,
+    },
+    DeployHistory {
+        snapshots: Vec<Snapshot>,
+    },
+    DeploySnapshot {
+        snapshot: Snapshot,
+    },
+    DeployRestore {
+        snapshot: Snapshot,
+    },
+    Import {
+        contract: DeployedContract,
+    },
+    ContractsList {
+        contracts: Vec<DeployedContract>,
+    },
+    ContractShow {
+        contract: DeployedContract,
+        entry_points: Vec<EntryPointInfo>,
+    },
+    Config {
+        file: Option<String>,
+        entries: Vec<ConfigEntry>,
+    },
+}
+
+/// A deployment stored in the deploy history.
+#[derive(Debug, Serialize)]
+pub(crate) struct Snapshot {
+    pub id: String,
+    pub contracts: Vec<DeployedContract>,
+}
+
+impl From<&DeployedContractsContainer> for Snapshot {
+    fn from(container: &DeployedContractsContainer) -> Self {
+        Self {
+            id: container.id().to_string(),
+            contracts: container.contracts().to_vec(),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// In the JSON format, redirects everything else printed to the standard output,
+    /// like the logs of the livenet client, to the standard error.
+    pub fn redirect_logs(&self) {
+        #[cfg(unix)]
+        if *self == OutputFormat::Json {
+            use std::os::fd::FromRawFd;
+
+            DOCUMENTS.get_or_init(|| {
+                let _ = std::io::stdout().flush();
+                // SAFETY: the duplicated descriptor is owned only by the returned file
+                unsafe {
+                    let stdout = libc::dup(libc::STDOUT_FILENO);
+                    libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO);
+                    File::from_raw_fd(stdout)
+                }
+            });
+        }
+    }
+
+    /// Prints the result of a successfully executed command.
+    pub fn print_success(&self, output: &CommandOutput) {
+        match self {
+            OutputFormat::Text => {
+                match output {
+                    CommandOutput::Call {
+                        result, receipt, ..
+                    } => {
+                        prettycli::info(&text(result));
+                        if let Some(receipt) = receipt {
+                            print_receipt(receipt);
+                        }
+                    }
+                    CommandOutput::GasEstimate { gas, .. } => {
+                        prettycli::info(&format!("Estimated gas: {} motes", gas))
+                    }
+                    CommandOutput::Events { contract, events } => events
+                        .iter()
+                        .for_each(|event| self.print_event(contract, event)),
+                    CommandOutput::Scenario { events, .. } => {
+                        for contract in events {
+                            prettycli::info(&format!("{} events:", contract.contract));
+                            print_events(&contract.events);
+                        }
+                    }
+                    CommandOutput::DeployHistory { snapshots } => {
+                        for snapshot in snapshots {
+                            let names = snapshot
+                                .contracts
+                                .iter()
+                                .map(DeployedContract::name)
+                                .collect::<Vec<_>>();
+                            prettycli::info(&format!("{} {}", snapshot.id, names.join(", ")));
+                        }
+                    }
+                    CommandOutput::DeploySnapshot { snapshot } => print_snapshot(snapshot),
+                    CommandOutput::ContractsList { contracts } => {
+                        for contract in contracts {
+                            prettycli::info(&format!(
+                                "{} {} {}",
+                                contract.name(),
+                                contract.package_hash(),
+                                contract.timestamp().unwrap_or("-")
+                            ));
+                        }
+                    }
+                    CommandOutput::ContractShow {
+                        contract,
+                        entry_points,
+                    } => print_contract(contract, entry_points),
+                    CommandOutput::Config { file, entries } => print_config(file, entries),
+                    CommandOutput::Import { contract } => prettycli::info(&format!(
+                        "Imported {}: {}",
+                        contract.name(),
+                        contract.package_hash()
+                    )),
+                    CommandOutput::DeployRestore { snapshot } => {
+                        prettycli::info(&format!("Restored deployment {}", snapshot.id));
+                        print_snapshot(snapshot);
+                    }
+                    _ => {}
+                }
+                prettycli::info("Command executed successfully");
+            }
+            OutputFormat::Json => {
+                let mut document = json!({ "status": "success" });
+                if let (Value::Object(document), Ok(Value::Object(output))) =
+                    (&mut document, serde_json::to_value(output))
+                {
+                    document.extend(output);
+                }
+                print_document(&document);
+            }
+        }
+    }
+
+    /// Prints a single event of the given contract.
+    ///
+    /// In the JSON format each event is printed in a separate line, as the same document
+    /// as the result of the `events` command listing only this event.
+    pub fn print_event(&self, contract: &str, event: &IndexedEvent) {
+        match self {
+            OutputFormat::Text => {
+                prettycli::info(&format!("#{} {}", event.index, event_text(&event.event)))
+            }
+            OutputFormat::Json => self.print_success(&CommandOutput::Events {
+                contract: contract.to_string(),
+                events: vec![event.clone()],
+            }),
+        }
+    }
+
+    /// Prints the error the command failed with.
+    pub fn print_error(&self, error: &Error) {
+        match self {
+            OutputFormat::Text => prettycli::error(&error.to_string()),
+            OutputFormat::Json => {
+                let document = json!({
+                    "status": "error",
+                    "error": {
+                        "kind": error.kind(),
+                        "code": error.exit_code(),
+                        "message": error.to_string(),
+                    }
+                });
+                print_document(&document);
+            }
+        }
+    }
+}
+
+fn print_document(document: &Value) {
+    match DOCUMENTS.get() {
+        Some(mut stdout) => {
+            let _ = writeln!(stdout, "{}", document);
+        }
+        None => println!("{}", document),
+    }
+}
+
+/// Converts a value decoded by [crate::args::decode] into a JSON value.
+///
+/// Custom types are already decoded to JSON, other values are kept as strings,
+/// so big numbers and keys are not reformatted.
+pub(crate) fn decoded_value(decoded: String, ty: &Type) -> Value {
+    match &ty.0 {
+        NamedCLType::Unit => Value::Null,
+        ty if args::contains_custom_type(ty) => {
+            Value::from_str(&decoded).unwrap_or(Value::String(decoded))
+        }
+        _ => Value::String(decoded),
+    }
+}
+
+fn print_contract(contract: &DeployedContract, entry_points: &[EntryPointInfo]) {
+    prettycli::info(&format!("{}:", contract.name()));
+    if let Value::Object(record) = json!(contract) {
+        for (key, value) in record.iter().filter(|(key, _)| *key != "name") {
+            prettycli::info(&format!("  {}: {}", key, text(value)));
+        }
+    }
+    prettycli::info("Entry points:");
+    for entry_point in entry_points {
+        let arguments = entry_point
+            .arguments
+            .iter()
+            .map(|arg| match arg.optional {
+                true => format!("{}: Option<{}>", arg.name, arg.ty),
+                false => format!("{}: {}", arg.name, arg.ty),
+            })
+            .collect::<Vec<_>>();
+        let mutability = if entry_point.is_mutable { "mut " } else { "" };
+        prettycli::info(&format!(
+            "  {}{}({}) -> {}",
+            mutability,
+            entry_point.name,
+            arguments.join(", "),
+            entry_point.return_type
+        ));
+    }
+}
+
+fn print_config(file: &Option<String>, entries: &[ConfigEntry]) {
+    match file {
+        Some(file) => prettycli::info(&format!("Config file: {}", file)),
+        None => prettycli::info(&format!("Config file: no {} found", config::CONFIG_FILE)),
+    }
+    for entry in entries {
+        let source = match entry.source {
+            ConfigSource::Flag => "flag",
+            ConfigSource::Env => "environment",
+            ConfigSource::ConfigFile => "config file",
+            ConfigSource::Unset => "unset",
+        };
+        prettycli::info(&format!(
+            "  {}: {} ({}, {})",
+            entry.key,
+            entry.value.as_deref().unwrap_or("-"),
+            source,
+            entry.env
+        ));
+    }
+}
+
+fn print_snapshot(snapshot: &Snapshot) {
+    prettycli::info(&format!("Deployment {}:", snapshot.id));
+    for contract in &snapshot.contracts {
+        prettycli::info(&format!(
+            "  {}: {}",
+            contract.name(),
+            contract.package_hash()
+        ));
+    }
+}
+
+fn print_receipt(receipt: &Receipt) {
+    prettycli::info("Receipt:");
+    if let Some(deploy_hash) = &receipt.deploy_hash {
+        prettycli::info(&format!("  Deploy hash: {}", deploy_hash));
+    }
+    prettycli::info(&format!("  Caller: {}", receipt.caller));
+    if let Some(gas_used) = receipt.gas_used {
+        prettycli::info(&format!("  Gas used: {}", gas_used));
+    }
+    prettycli::info(&format!("  Attached value: {}", receipt.attached_value));
+    if let Some(block_height) = receipt.block_height {
+        prettycli::info(&format!("  Block height: {}", block_height));
+    }
+    if let Some(block_time) = &receipt.block_time {
+        prettycli::info(&format!("  Block time: {}", block_time));
+    }
+    for contract in &receipt.events {
+        prettycli::info(&format!("  {} events:", contract.contract));
+        print_events(&contract.events);
+    }
+}
+
+fn print_events(events: &[DecodedEvent]) {
+    for event in events {
+        prettycli::info(&format!("    {}", event_text(event)));
+    }
+}
+
+fn event_text(event: &DecodedEvent) -> String {
+    match &event.data {
+        Value::Null => event.name.clone(),
+        data => format!("{} {}", event.name, data),
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => "".to_string(),
+        Value::String(value) => value.clone(),
+        value => serde_json::to_string_pretty(value).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use odra::schema::casper_contract_schema::{NamedCLType, Type};
+    use serde_json::json;
+
+    #[test]
+    fn test_decoded_value() {
+        let custom = Type(NamedCLType::Custom("NameTokenMetadata".to_string()));
+        let value = super::decoded_value(r#"{ "token_hash": "kpob" }"#.to_string(), &custom);
+        pretty_assertions::assert_eq!(value, json!({ "token_hash": "kpob" }));
+
+        let number = Type(NamedCLType::U256);
+        let value = super::decoded_value("1000".to_string(), &number);
+        pretty_assertions::assert_eq!(value, json!("1000"));
+
+        let unit = Type(NamedCLType::Unit);
+        let value = super::decoded_value("".to_string(), &unit);
+        pretty_assertions::assert_eq!(value, json!(null));
+    }
+}
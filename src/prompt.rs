@@ -0,0 +1,118 @@
+//! Prompts for the values of the required args missing in the input.
+use std::ffi::OsString;
+
+use rustyline::DefaultEditor;
+
+use crate::{args, args::CommandArg, types, CustomTypeSet};
+
+/// Reads the values of the given args from the terminal and returns them as command line flags.
+///
+/// Each value is validated as soon as it is entered, an invalid value is prompted for again.
+/// The values of a list are separated by commas. An optional arg is a payload of an enum variant,
+/// it is prompted for only if its variant is entered. Prompting stops if the input is interrupted,
+/// the args left are then reported by the parser.
+pub(crate) fn prompt(missing: &[CommandArg], types: &CustomTypeSet) -> Vec<OsString> {
+    let mut flags = vec![];
+    if missing.is_empty() {
+        return flags;
+    }
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            prettycli::error(&err.to_string());
+            return flags;
+        }
+    };
+
+    let selectors = missing
+        .iter()
+        .filter(|arg| args::is_variant_selector(arg))
+        .map(|arg| arg.name.as_str())
+        .collect::<Vec<_>>();
+    let mut selected = vec![];
+    for arg in missing {
+        let is_selected = args::payload_variant(&selectors, &arg.name)
+            .is_some_and(|(selector, variant)| selected.contains(&(selector, variant.to_string())));
+        if !arg.required && !is_selected {
+            continue;
+        }
+        let ty = match arg.is_list_element {
+            true => format!("Vec<{}>", types::type_name(&arg.ty)),
+            false => types::type_name(&arg.ty),
+        };
+        match arg.description.is_empty() {
+            true => prettycli::info(&format!("{} ({})", arg.name, ty)),
+            false => prettycli::info(&format!("{} ({}): {}", arg.name, ty, arg.description)),
+        }
+
+        loop {
+            let Ok(line) = editor.readline(&format!("{}: ", arg.name)) else {
+                return flags;
+            };
+            match values(arg, &line, types) {
+                Ok(values) => {
+                    if args::is_variant_selector(arg) {
+                        selected.extend(
+                            values
+                                .iter()
+                                .map(|variant| (arg.name.as_str(), variant.to_lowercase())),
+                        );
+                    }
+                    flags.extend(
+                        values
+                            .into_iter()
+                            .map(|value| OsString::from(format!("--{}={}", arg.name, value))),
+                    );
+                    break;
+                }
+                Err(err) => prettycli::error(&err),
+            }
+        }
+    }
+    flags
+}
+
+fn values(arg: &CommandArg, line: &str, types: &CustomTypeSet) -> Result<Vec<String>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Err(format!("The `{}` arg is required", arg.name));
+    }
+    let values = match arg.is_list_element {
+        true => line.split(',').map(str::trim).collect(),
+        false => vec![line],
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            args::validate(arg, value, types)
+                .map(|_| value.to_string())
+                .map_err(|err| err.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod t {
+    use odra::schema::casper_contract_schema::NamedCLType;
+
+    use crate::{args::CommandArg, CustomTypeSet};
+
+    #[test]
+    fn test_values() {
+        let types = CustomTypeSet::new();
+        let amount = CommandArg::new("amount", "", NamedCLType::U256, true, false);
+        let ids = CommandArg::new("ids", "", NamedCLType::U32, true, true);
+
+        pretty_assertions::assert_eq!(
+            super::values(&amount, " 100 ", &types),
+            Ok(vec!["100".to_string()])
+        );
+        assert!(super::values(&amount, "abc", &types).is_err());
+        assert!(super::values(&amount, "", &types).is_err());
+        pretty_assertions::assert_eq!(
+            super::values(&ids, "1, 2,3", &types),
+            Ok(vec!["1".to_string(), "2".to_string(), "3".to_string()])
+        );
+        assert!(super::values(&ids, "1,x", &types).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Interactive shell running the commands against a single host environment.
+use std::path::PathBuf;
+
+use clap::{ArgMatches, Command};
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+
+pub(crate) const SHELL_SUBCOMMAND: &str = "shell";
+const PROMPT: &str = "odra> ";
+const HISTORY_FILE: &str = ".odra_cli_history";
+const EXIT_COMMANDS: [&str; 2] = ["exit", "quit"];
+
+/// Returns the command starting the shell.
+pub(crate) fn command() -> Command {
+    Command::new(SHELL_SUBCOMMAND)
+        .about("Starts an interactive shell, which keeps the host environment between commands")
+}
+
+/// Reads the lines of the shell until it is exited.
+///
+/// Each line is parsed by the given command, the subcommands of the command listed in
+/// `available` are completed with the Tab key. The parsed input is passed to `exec`.
+/// The history is kept in the `.odra_cli_history` file in the home directory.
+pub(crate) fn run(cmd: &Command, available: &[&str], mut exec: impl FnMut(ArgMatches)) {
+    let mut cmd = cmd.clone().no_binary_name(true);
+    cmd.build();
+
+    let mut editor = match Editor::<ShellHelper, DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(err) => return prettycli::error(&err.to_string()),
+    };
+    editor.set_helper(Some(ShellHelper::new(&cmd, available)));
+    let history = history_path();
+    if let Some(path) = &history {
+        // there is no history before the first session
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            // Ctrl-C discards the line
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                prettycli::error(&err.to_string());
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+        if EXIT_COMMANDS.contains(&line) {
+            break;
+        }
+
+        let Some(words) = shlex::split(line) else {
+            prettycli::error("Invalid quoting");
+            continue;
+        };
+        match cmd.try_get_matches_from_mut(words) {
+            Ok(matches) => exec(matches),
+            Err(err) => {
+                let _ = err.print();
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Err(err) = editor.save_history(path) {
+            prettycli::error(&format!("Couldn't save the history: {}", err));
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+/// Completes the names of the subcommands and the flags of the command tree.
+struct ShellHelper {
+    cmd: Command,
+    available: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new(cmd: &Command, available: &[&str]) -> Self {
+        Self {
+            cmd: cmd.clone(),
+            available: available.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    fn candidates(&self, words: &[&str], word: &str) -> Vec<String> {
+        // the words are matched against the command tree, the unknown ones are the values
+        let cmd = words.iter().fold(&self.cmd, |cmd, word| {
+            cmd.find_subcommand(word).unwrap_or(cmd)
+        });
+
+        let candidates: Vec<String> = if word.starts_with('-') {
+            cmd.get_arguments()
+                .filter(|arg| !arg.is_hide_set())
+                .filter_map(|arg| arg.get_long())
+                .map(|long| format!("--{}", long))
+                .collect()
+        } else if words.is_empty() {
+            self.available
+                .iter()
+                .cloned()
+                .chain(EXIT_COMMANDS.iter().map(|name| name.to_string()))
+                .collect()
+        } else {
+            cmd.get_subcommands()
+                .map(|subcommand| subcommand.get_name().to_string())
+                .collect()
+        };
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect()
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line
+            .rfind(char::is_whitespace)
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        let (words, word) = line.split_at(start);
+        let words = words.split_whitespace().collect::<Vec<_>>();
+
+        let mut candidates = self
+            .candidates(&words, word)
+            .into_iter()
+            .map(|candidate| Pair {
+                display: candidate.clone(),
+                replacement: format!("{} ", candidate),
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| a.display.cmp(&b.display));
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+#[cfg(test)]
+mod t {
+    use clap::{Arg, Command};
+
+    use super::ShellHelper;
+
+    #[test]
+    fn test_candidates() {
+        let cmd = Command::new("cli")
+            .subcommand(
+                Command::new("contract").subcommand(
+                    Command::new("Erc20")
+                        .subcommand(Command::new("transfer").arg(Arg::new("to").long("to")))
+                        .subcommand(Command::new("balance_of")),
+                ),
+            )
+            .subcommand(Command::new("deploy"));
+        let helper = ShellHelper::new(&cmd, &["contract"]);
+
+        pretty_assertions::assert_eq!(helper.candidates(&[], ""), vec!["contract", "exit", "quit"]);
+        pretty_assertions::assert_eq!(helper.candidates(&["contract"], "E"), vec!["Erc20"]);
+        pretty_assertions::assert_eq!(
+            helper.candidates(&["contract", "Erc20"], ""),
+            vec!["transfer", "balance_of"]
+        );
+        pretty_assertions::assert_eq!(
+            helper.candidates(&["contract", "Erc20", "transfer"], "--"),
+            vec!["--to"]
+        );
+    }
+}
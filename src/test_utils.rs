@@ -1,4 +1,5 @@
 use odra::{
+    casper_event_standard,
     casper_types::U512,
     schema::{
         casper_contract_schema::{Access, Argument, Entrypoint, NamedCLType, Type},
@@ -64,13 +65,10 @@ pub fn mock_command_args() -> Vec<CommandArg> {
 }
 
 pub fn custom_types() -> CustomTypeSet {
-    let mut types =
-        CustomTypeSet::from_iter(PaymentVoucher::schema_types().into_iter().filter_map(|t| t));
-    types.extend(
-        NameTokenMetadata::schema_types()
-            .into_iter()
-            .filter_map(|t| t),
-    );
+    let mut types = CustomTypeSet::from_iter(PaymentVoucher::schema_types().into_iter().flatten());
+    types.extend(NameTokenMetadata::schema_types().into_iter().flatten());
+    types.extend(PaymentMethod::schema_types().into_iter().flatten());
+    types.extend(NameRegistered::schema_types().into_iter().flatten());
     types
 }
 
@@ -131,3 +129,32 @@ impl NameMintInfo {
         }
     }
 }
+
+#[odra::odra_type]
+pub enum PaymentMethod {
+    Free,
+    Cspr(U512),
+    Token { token: Address, amount: U512 },
+}
+
+#[odra::event]
+pub struct NameRegistered {
+    pub label: String,
+    pub owner: Address,
+}
+
+#[odra::module]
+pub struct NameRegistry {
+    last_label: odra::Var<String>,
+}
+
+#[odra::module]
+impl NameRegistry {
+    pub fn register(&mut self, label: String) {
+        self.last_label.set(label);
+    }
+
+    pub fn last_label(&self) -> Option<String> {
+        self.last_label.get()
+    }
+}
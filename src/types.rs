@@ -14,6 +14,7 @@ use odra::{
 use thiserror::Error;
 
 #[derive(Debug, Error)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[error("Invalid hex string")]
     InvalidHexString,
@@ -114,10 +115,48 @@ pub(crate) fn named_cl_type_to_cl_type(ty: &NamedCLType) -> CLType {
     }
 }
 
+/// Returns the Rust name of the type, as used in the contract code.
+pub(crate) fn type_name(ty: &NamedCLType) -> String {
+    match ty {
+        NamedCLType::Bool => "bool".to_string(),
+        NamedCLType::I32 => "i32".to_string(),
+        NamedCLType::I64 => "i64".to_string(),
+        NamedCLType::U8 => "u8".to_string(),
+        NamedCLType::U32 => "u32".to_string(),
+        NamedCLType::U64 => "u64".to_string(),
+        NamedCLType::U128 => "U128".to_string(),
+        NamedCLType::U256 => "U256".to_string(),
+        NamedCLType::U512 => "U512".to_string(),
+        NamedCLType::Unit => "()".to_string(),
+        NamedCLType::String => "String".to_string(),
+        NamedCLType::Key => "Address".to_string(),
+        NamedCLType::URef => "URef".to_string(),
+        NamedCLType::PublicKey => "PublicKey".to_string(),
+        NamedCLType::Option(ty) => format!("Option<{}>", type_name(ty)),
+        NamedCLType::List(ty) => format!("Vec<{}>", type_name(ty)),
+        NamedCLType::ByteArray(n) => format!("[u8; {}]", n),
+        NamedCLType::Result { ok, err } => {
+            format!("Result<{}, {}>", type_name(ok), type_name(err))
+        }
+        NamedCLType::Map { key, value } => {
+            format!("BTreeMap<{}, {}>", type_name(key), type_name(value))
+        }
+        NamedCLType::Tuple1(ty) => format!("({},)", type_name(&ty[0])),
+        NamedCLType::Tuple2(ty) => format!("({}, {})", type_name(&ty[0]), type_name(&ty[1])),
+        NamedCLType::Tuple3(ty) => format!(
+            "({}, {}, {})",
+            type_name(&ty[0]),
+            type_name(&ty[1]),
+            type_name(&ty[2])
+        ),
+        NamedCLType::Custom(name) => name.clone(),
+    }
+}
+
 pub(crate) fn vec_into_bytes(ty: &NamedCLType, input: Vec<&str>) -> TypeResult<Vec<u8>> {
     let mut result = _to_bytes(input.len() as u32)?;
     for value in input {
-        result.extend(into_bytes(ty, &value)?);
+        result.extend(into_bytes(ty, value)?);
     }
     Ok(result)
 }
@@ -160,11 +199,11 @@ pub(crate) fn into_bytes(ty: &NamedCLType, input: &str) -> TypeResult<Vec<u8>> {
             if input.starts_with("err:") {
                 let value = input.strip_prefix("err:").unwrap();
                 result.push(RESULT_ERR_TAG);
-                result.extend(into_bytes(err, &value)?);
+                result.extend(into_bytes(err, value)?);
             } else if input.starts_with("ok:") {
                 let value = input.strip_prefix("ok:").unwrap();
                 result.push(RESULT_OK_TAG);
-                result.extend(into_bytes(ok, &value)?);
+                result.extend(into_bytes(ok, value)?);
             } else {
                 return Err(Error::Formatting("Invalid result variant".to_string()));
             }
@@ -215,8 +254,8 @@ pub(crate) fn into_bytes(ty: &NamedCLType, input: &str) -> TypeResult<Vec<u8>> {
 
             let mut result = _to_bytes(parts.len() as u32)?;
             for (k, v) in parts.iter() {
-                result.extend(into_bytes(&key, k)?);
-                result.extend(into_bytes(&value, v)?);
+                result.extend(into_bytes(key, k)?);
+                result.extend(into_bytes(value, v)?);
             }
             Ok(result)
         }
@@ -259,7 +298,9 @@ pub(crate) fn into_bytes(ty: &NamedCLType, input: &str) -> TypeResult<Vec<u8>> {
                 Err(e) => Err(e),
             }
         }
-        NamedCLType::Custom(_) => unreachable!("should not be here"),
+        NamedCLType::Custom(_) => Err(Error::Formatting(
+            "Custom type value must be passed as JSON".to_string(),
+        )),
     }
 }
 
@@ -279,10 +320,10 @@ pub(crate) fn from_bytes<'a>(ty: &NamedCLType, input: &'a [u8]) -> TypeResult<(S
         NamedCLType::URef => call_from_bytes!(URef, input),
         NamedCLType::PublicKey => call_from_bytes!(PublicKey, input),
         NamedCLType::Option(ty) => {
-            if input.get(0) == Some(&OPTION_NONE_TAG) {
-                Ok(("null".to_string(), input))
+            if input.first() == Some(&OPTION_NONE_TAG) {
+                Ok(("null".to_string(), &input[1..]))
             } else {
-                from_bytes(&*ty, &input[1..])
+                from_bytes(ty, &input[1..])
             }
         }
         NamedCLType::Result { ok, err } => {
@@ -324,7 +365,7 @@ pub(crate) fn from_bytes<'a>(ty: &NamedCLType, input: &'a [u8]) -> TypeResult<(S
             for _ in 0..num_keys {
                 let (v, rem) = from_bytes(ty, stream)?;
                 result.push_str(&v);
-                result.push_str(",");
+                result.push(',');
                 stream = rem;
             }
             if num_keys > 0 {
@@ -337,9 +378,9 @@ pub(crate) fn from_bytes<'a>(ty: &NamedCLType, input: &'a [u8]) -> TypeResult<(S
 
             let mut hex = "0x".to_string();
             let mut dec = "".to_string();
-            for i in 0..size {
-                dec.push_str(&format!("{}, ", input[i]));
-                hex.push_str(&format!("{:02x}", input[i]));
+            for byte in input.iter().take(size) {
+                dec.push_str(&format!("{}, ", byte));
+                hex.push_str(&format!("{:02x}", byte));
             }
 
             // remove trailing comma
@@ -366,7 +407,9 @@ pub(crate) fn from_bytes<'a>(ty: &NamedCLType, input: &'a [u8]) -> TypeResult<(S
             }
             Ok((result, stream))
         }
-        NamedCLType::Custom(_) => unreachable!("should not be here"),
+        NamedCLType::Custom(_) => Err(Error::Formatting(
+            "Custom type value must be decoded with its schema".to_string(),
+        )),
     }
 }
 